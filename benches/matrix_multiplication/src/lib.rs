@@ -46,11 +46,12 @@ pub fn matmul_naive(a: &Matrix<f64>, b: &Matrix<f64>) -> Matrix<f64> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use matrix::Matrix;
+    use matrix::{bf16, f16, Kahan, Matrix, Pairwise};
     use test::Bencher;
 
     const NUM_ROWS: usize = 1_000;
     const NUM_COLUMNS: usize = 1_000;
+    const SEED: u64 = 0;
 
     #[test]
     fn naive_matrix_multiplication() {
@@ -65,14 +66,61 @@ mod tests {
 
     #[bench]
     fn matmul_benchmark(b: &mut Bencher) {
-        let a: Matrix<f64> = Matrix::ones(NUM_ROWS, NUM_COLUMNS);
+        let a: Matrix<f64> = Matrix::random_uniform(NUM_ROWS, NUM_COLUMNS, -1., 1., SEED);
 
         b.iter(|| Matrix::mul(&a, &a));
     }
 
+    #[bench]
+    fn matmul_f32_benchmark(b: &mut Bencher) {
+        let a: Matrix<f32> = Matrix::random_uniform(NUM_ROWS, NUM_COLUMNS, -1., 1., SEED);
+
+        b.iter(|| Matrix::mul(&a, &a));
+    }
+
+    #[bench]
+    fn matmul_f32_pairwise_benchmark(b: &mut Bencher) {
+        let a: Matrix<f32> = Matrix::random_uniform(NUM_ROWS, NUM_COLUMNS, -1., 1., SEED);
+
+        b.iter(|| Matrix::mul_with(&a, &a, Pairwise));
+    }
+
+    #[bench]
+    fn matmul_f32_kahan_benchmark(b: &mut Bencher) {
+        let a: Matrix<f32> = Matrix::random_uniform(NUM_ROWS, NUM_COLUMNS, -1., 1., SEED);
+
+        b.iter(|| Matrix::mul_with(&a, &a, Kahan));
+    }
+
+    #[bench]
+    fn matmul_f16_f32_benchmark(b: &mut Bencher) {
+        let a: Matrix<f16> = Matrix::random_uniform(
+            NUM_ROWS,
+            NUM_COLUMNS,
+            f16::from_f32(-1.),
+            f16::from_f32(1.),
+            SEED,
+        );
+
+        b.iter(|| Matrix::mul_f32(&a, &a));
+    }
+
+    #[bench]
+    fn matmul_bf16_f32_benchmark(b: &mut Bencher) {
+        let a: Matrix<bf16> = Matrix::random_uniform(
+            NUM_ROWS,
+            NUM_COLUMNS,
+            bf16::from_f32(-1.),
+            bf16::from_f32(1.),
+            SEED,
+        );
+
+        b.iter(|| Matrix::mul_f32(&a, &a));
+    }
+
     #[bench]
     fn matmul_naive_benchmark(b: &mut Bencher) {
-        let a: Matrix<f64> = Matrix::ones(NUM_ROWS, NUM_COLUMNS);
+        let a: Matrix<f64> = Matrix::random_uniform(NUM_ROWS, NUM_COLUMNS, -1., 1., SEED);
 
         b.iter(|| matmul_naive(&a, &a));
     }
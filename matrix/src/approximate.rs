@@ -0,0 +1,381 @@
+use super::{Matrix, Storage};
+use num_traits::Float;
+use std::fmt;
+
+/// A floating point type whose values can be compared by their distance
+/// in units in the last place (ULPs), that is, by the number of
+/// representable values between them.
+pub trait Ulps: Float {
+    /// Returns the number of steps from one representable value to the
+    /// next needed to go from `self` to `other`, or `None` if either is NaN.
+    ///
+    /// Zero and negative zero are zero ULPs apart.
+    fn ulps(self, other: Self) -> Option<u64>;
+}
+
+macro_rules! impl_ulps {
+    ($($t:ty => $bits:ty),*) => {
+        $(
+            impl Ulps for $t {
+                fn ulps(self, other: Self) -> Option<u64> {
+                    if self.is_nan() || other.is_nan() {
+                        return None;
+                    }
+                    // Maps the bits to integers that are ordered like the
+                    // floats, with both zeros mapped to zero.
+                    let ordered = |x: $t| {
+                        let bits = x.to_bits() as $bits;
+                        if bits < 0 {
+                            <$bits>::MIN.wrapping_sub(bits)
+                        } else {
+                            bits
+                        }
+                    };
+                    let distance = ordered(self) as i128 - ordered(other) as i128;
+                    Some(distance.unsigned_abs() as u64)
+                }
+            }
+        )*
+    };
+}
+
+impl_ulps!(f32 => i32, f64 => i64);
+
+impl<T, S> Matrix<T, S>
+where
+    T: Float,
+    S: Storage<T>,
+{
+    /// Returns `true` if the matrices have the same shape and each pair of
+    /// corresponding elements `x` and `y` satisfies
+    /// `|x - y| <= max(abs_tol, rel_tol * max(|x|, |y|))`.
+    ///
+    /// NaN is not approximately equal to anything, but infinities of the
+    /// same sign are approximately equal.
+    pub fn approx_eq<SO>(&self, other: &Matrix<T, SO>, abs_tol: T, rel_tol: T) -> bool
+    where
+        SO: Storage<T>,
+    {
+        self.shape() == other.shape() && self.worst_mismatch(other, abs_tol, rel_tol).is_none()
+    }
+
+    /// Returns `true` if the matrices have the same shape and each pair of
+    /// corresponding elements is at most `max_ulps` units in the last place
+    /// apart.
+    pub fn ulps_eq<SO>(&self, other: &Matrix<T, SO>, max_ulps: u64) -> bool
+    where
+        T: Ulps,
+        SO: Storage<T>,
+    {
+        self.shape() == other.shape()
+            && (0..self.num_rows).all(|i| {
+                (0..self.num_columns).all(|j| {
+                    let (x, y) = (self[(i, j)], other[(i, j)]);
+                    x.ulps(y).is_some_and(|ulps| ulps <= max_ulps)
+                })
+            })
+    }
+
+    /// Returns the pair of corresponding elements that most exceeds the
+    /// tolerance, relative to the tolerance, with the number of such pairs,
+    /// or `None` if all pairs are within it.
+    fn worst_mismatch<SO>(
+        &self,
+        other: &Matrix<T, SO>,
+        abs_tol: T,
+        rel_tol: T,
+    ) -> Option<Mismatch<T>>
+    where
+        SO: Storage<T>,
+    {
+        let mut worst: Option<Mismatch<T>> = None;
+        let mut count = 0;
+        for i in 0..self.num_rows {
+            for j in 0..self.num_columns {
+                let (x, y) = (self[(i, j)], other[(i, j)]);
+                if x == y {
+                    continue;
+                }
+                let tolerance = abs_tol.max(rel_tol * x.abs().max(y.abs()));
+                let excess = (x - y).abs() / tolerance;
+                // NaN differences exceed any tolerance.
+                if excess <= T::one() {
+                    continue;
+                }
+                count += 1;
+                if worst
+                    .as_ref()
+                    .is_none_or(|w| (excess.is_nan() && !w.excess.is_nan()) || excess > w.excess)
+                {
+                    worst = Some(Mismatch {
+                        position: (i, j),
+                        left: x,
+                        right: y,
+                        excess,
+                        count: 0,
+                    });
+                }
+            }
+        }
+        worst.map(|w| Mismatch { count, ..w })
+    }
+}
+
+/// The worst pair of elements found by `worst_mismatch`.
+struct Mismatch<T> {
+    position: (usize, usize),
+    left: T,
+    right: T,
+    excess: T,
+    count: usize,
+}
+
+/// Panics with a report of the worst mismatch unless `left` and `right`
+/// are approximately equal. Used by [`assert_matrix_approx_eq!`].
+///
+/// Without explicit tolerances, both are the square root of the machine
+/// epsilon.
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_approx_eq<T, SL, SR>(
+    left: &Matrix<T, SL>,
+    right: &Matrix<T, SR>,
+    tolerances: Option<(T, T)>,
+) where
+    T: Float + fmt::Debug,
+    SL: Storage<T>,
+    SR: Storage<T>,
+{
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn shape_failed(left: (usize, usize), right: (usize, usize)) -> ! {
+        panic!(
+            "assertion `left ≈ right` failed: the shapes differ\n  \
+            left shape: {left:?}\n right shape: {right:?}"
+        );
+    }
+
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn values_failed<T: fmt::Debug>(
+        mismatch: Mismatch<T>,
+        num_elements: usize,
+        (abs_tol, rel_tol): (T, T),
+    ) -> ! {
+        let Mismatch {
+            position,
+            left,
+            right,
+            count,
+            ..
+        } = mismatch;
+        panic!(
+            "assertion `left ≈ right` failed: {count} of {num_elements} elements differ \
+            (abs_tol = {abs_tol:?}, rel_tol = {rel_tol:?})\n  \
+            worst at {position:?}: left = {left:?}, right = {right:?}"
+        );
+    }
+
+    if left.shape() != right.shape() {
+        shape_failed(left.shape(), right.shape());
+    }
+    let (abs_tol, rel_tol) = tolerances.unwrap_or_else(|| {
+        let tolerance = T::epsilon().sqrt();
+        (tolerance, tolerance)
+    });
+    if let Some(mismatch) = left.worst_mismatch(right, abs_tol, rel_tol) {
+        let num_elements = left.num_rows * left.num_columns;
+        values_failed(mismatch, num_elements, (abs_tol, rel_tol));
+    }
+}
+
+/// Asserts that two float matrices are approximately equal, as by
+/// [`Matrix::approx_eq`].
+///
+/// The tolerances may be given as `abs_tol = ..., rel_tol = ...`. Without
+/// them, both are the square root of the machine epsilon, about `1.5e-8`
+/// for `f64`.
+///
+/// On failure, the message reports how many elements differ and the pair
+/// that most exceeds the tolerance, with its position.
+///
+/// # Examples
+///
+/// ```
+/// use matrix::{assert_matrix_approx_eq, Matrix};
+///
+/// let a: Matrix<f64> = Matrix::from([[0.1, 0.2]]);
+/// let b = Matrix::from([[0.3, 0.4]]);
+///
+/// assert_matrix_approx_eq!(&a + &b, Matrix::from([[0.4, 0.6]]));
+/// assert_matrix_approx_eq!(a, b, abs_tol = 0.25, rel_tol = 0.);
+/// ```
+///
+/// [`Matrix::approx_eq`]: crate::Matrix::approx_eq
+#[macro_export]
+macro_rules! assert_matrix_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::__assert_approx_eq(&$left, &$right, ::std::option::Option::None)
+    };
+    ($left:expr, $right:expr, abs_tol = $abs_tol:expr, rel_tol = $rel_tol:expr $(,)?) => {
+        $crate::__assert_approx_eq(
+            &$left,
+            &$right,
+            ::std::option::Option::Some(($abs_tol, $rel_tol)),
+        )
+    };
+}
+
+#[cfg(feature = "approx")]
+mod approx_traits {
+    use super::super::{Matrix, Storage};
+    use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+    impl<T, S> Matrix<T, S>
+    where
+        S: Storage<T>,
+    {
+        /// Returns `true` if the matrices have the same shape and `eq`
+        /// holds for every pair of corresponding elements.
+        fn all_pairs<F>(&self, other: &Self, eq: F) -> bool
+        where
+            F: Fn(&T, &T) -> bool,
+        {
+            self.shape() == other.shape()
+                && (0..self.num_rows)
+                    .all(|i| (0..self.num_columns).all(|j| eq(&self[(i, j)], &other[(i, j)])))
+        }
+    }
+
+    impl<T, S> AbsDiffEq for Matrix<T, S>
+    where
+        T: AbsDiffEq,
+        T::Epsilon: Clone,
+        S: Storage<T>,
+    {
+        type Epsilon = T::Epsilon;
+
+        fn default_epsilon() -> Self::Epsilon {
+            T::default_epsilon()
+        }
+
+        fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+            self.all_pairs(other, |x, y| x.abs_diff_eq(y, epsilon.clone()))
+        }
+    }
+
+    impl<T, S> RelativeEq for Matrix<T, S>
+    where
+        T: RelativeEq,
+        T::Epsilon: Clone,
+        S: Storage<T>,
+    {
+        fn default_max_relative() -> Self::Epsilon {
+            T::default_max_relative()
+        }
+
+        fn relative_eq(
+            &self,
+            other: &Self,
+            epsilon: Self::Epsilon,
+            max_relative: Self::Epsilon,
+        ) -> bool {
+            self.all_pairs(other, |x, y| {
+                x.relative_eq(y, epsilon.clone(), max_relative.clone())
+            })
+        }
+    }
+
+    impl<T, S> UlpsEq for Matrix<T, S>
+    where
+        T: UlpsEq,
+        T::Epsilon: Clone,
+        S: Storage<T>,
+    {
+        fn default_max_ulps() -> u32 {
+            T::default_max_ulps()
+        }
+
+        fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+            self.all_pairs(other, |x, y| x.ulps_eq(y, epsilon.clone(), max_ulps))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_approximate {
+    use crate::{Layout, Matrix, Ulps};
+
+    #[test]
+    fn approx_eq() {
+        let a: Matrix<f64> = Matrix::from([[1., 100.], [0., f64::INFINITY]]);
+        let b = Matrix::from([[1.05, 101.], [0.01, f64::INFINITY]]);
+
+        assert!(a.approx_eq(&b, 0.1, 0.01));
+        assert!(!a.approx_eq(&b, 0.1, 0.001));
+        assert!(!a.approx_eq(&b, 0.001, 0.01));
+        assert!(a.approx_eq(&b.to_layout(Layout::ColumnMajor), 0.1, 0.01));
+        assert!(!a.approx_eq(&Matrix::zeros(2, 1), 1., 1.));
+
+        let nan = Matrix::from([[f64::NAN]]);
+        assert!(!nan.approx_eq(&nan, 1., 1.));
+    }
+
+    #[test]
+    fn ulps() {
+        assert_eq!(1f64.ulps(1. + f64::EPSILON), Some(1));
+        assert_eq!(0f32.ulps(-0.), Some(0));
+        assert_eq!(
+            (-f32::MIN_POSITIVE).ulps(f32::MIN_POSITIVE),
+            Some(2 * 0x80_0000)
+        );
+        assert_eq!(f64::NAN.ulps(1.), None);
+
+        let a: Matrix<f32> = Matrix::from([[0.1 + 0.2, 1.]]);
+        let b = Matrix::from([[0.3, 1.]]);
+        assert!(a.ulps_eq(&b, 1));
+        assert!(!a.ulps_eq(&Matrix::from([[0.3, 1.0001]]), 4));
+    }
+
+    #[test]
+    fn assert_macro() {
+        let a: Matrix<f64> = Matrix::random_uniform(20, 20, -1., 1., 0);
+        let identity = Matrix::identity(20);
+
+        assert_matrix_approx_eq!(Matrix::mul(&a, &identity), a);
+        assert_matrix_approx_eq!(a, a.map(|x| x + 1e-3), abs_tol = 1e-2, rel_tol = 0.);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `left ≈ right` failed: 2 of 4 elements differ \
+                               (abs_tol = 0.01, rel_tol = 0.0)\n  \
+                               worst at (1, 0): left = 3.0, right = 3.5")]
+    fn assert_macro_reports_worst_element() {
+        let a: Matrix<f64> = Matrix::from([[1., 2.], [3., 4.]]);
+        let b = Matrix::from([[1., 2.1], [3.5, 4.]]);
+
+        assert_matrix_approx_eq!(a, b, abs_tol = 0.01, rel_tol = 0.);
+    }
+
+    #[test]
+    #[should_panic(expected = "the shapes differ")]
+    fn assert_macro_with_different_shapes() {
+        assert_matrix_approx_eq!(Matrix::<f32>::zeros(1, 2), Matrix::<f32>::zeros(2, 1));
+    }
+
+    #[cfg(feature = "approx")]
+    #[test]
+    fn approx_traits() {
+        use approx::{assert_abs_diff_eq, assert_relative_eq, assert_ulps_eq};
+
+        let a: Matrix<f64> = Matrix::from([[0.1 + 0.2, 1.]]);
+        let b = Matrix::from([[0.3, 1.]]);
+
+        assert_abs_diff_eq!(a, b);
+        assert_relative_eq!(a, b, max_relative = 1e-12);
+        assert_ulps_eq!(a, b, max_ulps = 1);
+        approx::assert_abs_diff_ne!(a, Matrix::from([[0.3, 1.1]]));
+    }
+}
@@ -0,0 +1,483 @@
+use super::npy::{check_dtype, format_error, read_elements};
+use super::{Layout, Matrix, MatrixError, NpyElement, Storage};
+use std::io::{self, BufWriter, Read, Write};
+use std::{mem, slice};
+
+/// The first bytes of a file, which detect the mangling of line endings
+/// and of the eighth bit, as those of PNG do.
+const MAGIC: &[u8; 8] = b"\x89MTX\r\n\x1a\n";
+
+const VERSION: u16 = 1;
+
+/// Length of the header, which keeps the data aligned to 64 bytes.
+const HEADER_LEN: usize = 64;
+
+/// Length of the CRC-32 that follows the data.
+const FOOTER_LEN: usize = 4;
+
+/// The header of a binary matrix file.
+///
+/// It holds, in order and in little-endian byte order, the magic bytes,
+/// the version as a `u16`, the dtype of the elements as two ASCII bytes,
+/// as in `.npy` files, the layout (0 for row-major, 1 for column-major),
+/// the byte order of the data (0 for little-endian, 1 for big-endian),
+/// two reserved bytes, and the numbers of rows and columns as `u64`s. The
+/// rest of its 64 bytes are reserved.
+struct Header {
+    num_rows: usize,
+    num_columns: usize,
+    layout: Layout,
+    /// Whether the data is in the opposite byte order to the native one.
+    swap: bool,
+}
+
+impl Header {
+    fn to_bytes<T: NpyElement>(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0; HEADER_LEN];
+        bytes[..8].copy_from_slice(MAGIC);
+        bytes[8..10].copy_from_slice(&VERSION.to_le_bytes());
+        bytes[10..12].copy_from_slice(T::DTYPE.as_bytes());
+        bytes[12] = (self.layout == Layout::ColumnMajor) as u8;
+        bytes[13] = cfg!(target_endian = "big") as u8;
+        bytes[16..24].copy_from_slice(&(self.num_rows as u64).to_le_bytes());
+        bytes[24..32].copy_from_slice(&(self.num_columns as u64).to_le_bytes());
+        bytes
+    }
+
+    /// Parses and validates the header of a file of elements of type `T`.
+    fn parse<T: NpyElement>(bytes: &[u8; HEADER_LEN]) -> Result<Self, MatrixError> {
+        if &bytes[..8] != MAGIC {
+            return Err(format_error("not a binary matrix file"));
+        }
+        let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+        if version != VERSION {
+            return Err(format_error(format!("unsupported version {version}")));
+        }
+        let dtype = String::from_utf8_lossy(&bytes[10..12]);
+        check_dtype::<T>(&dtype, &dtype)?;
+        let layout = match bytes[12] {
+            0 => Layout::RowMajor,
+            1 => Layout::ColumnMajor,
+            layout => return Err(format_error(format!("invalid layout {layout}"))),
+        };
+        let big_endian = match bytes[13] {
+            0 => false,
+            1 => true,
+            byte_order => return Err(format_error(format!("invalid byte order {byte_order}"))),
+        };
+
+        let dimension = |range: std::ops::Range<usize>| {
+            let dimension = u64::from_le_bytes(bytes[range].try_into().unwrap());
+            usize::try_from(dimension).map_err(|_| format_error("the matrix is too large"))
+        };
+        Ok(Self {
+            num_rows: dimension(16..24)?,
+            num_columns: dimension(24..32)?,
+            layout,
+            swap: big_endian != cfg!(target_endian = "big"),
+        })
+    }
+
+    /// Returns the number of bytes of data of elements of type `T`.
+    fn num_bytes<T>(&self) -> Result<usize, MatrixError> {
+        self.num_rows
+            .checked_mul(self.num_columns)
+            .and_then(|len| len.checked_mul(mem::size_of::<T>()))
+            .filter(|&num_bytes| num_bytes <= isize::MAX as usize - HEADER_LEN - FOOTER_LEN)
+            .ok_or_else(|| format_error("the matrix is too large"))
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: NpyElement,
+{
+    /// Reads a matrix written by [`write_binary`], in either byte order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Format`] if the file is not a binary matrix
+    /// file, if its dtype is not the one of `T`, if it is truncated or if
+    /// its checksum does not match its contents, and [`MatrixError::Io`] if
+    /// reading fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matrix::{Matrix, MatrixError};
+    ///
+    /// let a: Matrix<f64> = Matrix::random_normal(3, 4, 0., 1., 0);
+    /// let mut file = Vec::new();
+    /// a.write_binary(&mut file)?;
+    ///
+    /// assert_eq!(Matrix::read_binary(&file[..])?, a);
+    ///
+    /// file[100] ^= 1;
+    /// assert!(matches!(Matrix::<f64>::read_binary(&file[..]), Err(MatrixError::Format { .. })));
+    /// # Ok::<(), MatrixError>(())
+    /// ```
+    ///
+    /// [`write_binary`]: Matrix::write_binary
+    pub fn read_binary<R: Read>(mut reader: R) -> Result<Self, MatrixError> {
+        let mut bytes = [0; HEADER_LEN];
+        read_exact(&mut reader, &mut bytes)?;
+        let header = Header::parse::<T>(&bytes)?;
+        let mut crc = Crc32::new();
+        crc.update(&bytes);
+
+        let num_bytes = header.num_bytes::<T>()?;
+        let data = read_elements(&mut reader, num_bytes, header.swap, |chunk| {
+            crc.update(chunk)
+        })
+        .map_err(truncated)?;
+        let mut footer = [0; FOOTER_LEN];
+        read_exact(&mut reader, &mut footer)?;
+        check_crc(u32::from_le_bytes(footer), crc.finish())?;
+
+        Ok(Self::from_layout(
+            header.num_rows,
+            header.num_columns,
+            data,
+            header.layout,
+        ))
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    T: NpyElement,
+    S: Storage<T>,
+{
+    /// Writes the matrix to a compact binary file: a 64-byte header with
+    /// the dtype, shape and layout of the matrix, its elements as they are
+    /// stored in memory, without padding, and a CRC-32 of both.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Io`] if writing fails.
+    pub fn write_binary<W: Write>(&self, writer: W) -> Result<(), MatrixError> {
+        let mut writer = BufWriter::new(writer);
+        let header = Header {
+            num_rows: self.num_rows,
+            num_columns: self.num_columns,
+            layout: self.layout,
+            swap: false,
+        };
+        let header = header.to_bytes::<T>();
+        let mut crc = Crc32::new();
+        crc.update(&header);
+        writer.write_all(&header)?;
+
+        for lane in self.lanes() {
+            // SAFETY: the `NpyElement` types have no padding bytes.
+            let bytes = unsafe {
+                slice::from_raw_parts(lane.as_ptr().cast::<u8>(), mem::size_of_val(lane))
+            };
+            crc.update(bytes);
+            writer.write_all(bytes)?;
+        }
+        writer.write_all(&crc.finish().to_le_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mmap")]
+pub use mmap::MmapStorage;
+
+#[cfg(feature = "mmap")]
+mod mmap {
+    use super::{check_crc, format_error, Crc32, Header, FOOTER_LEN, HEADER_LEN};
+    use crate::{Matrix, MatrixError, NpyElement, Storage};
+    use memmap2::Mmap;
+    use std::fs::File;
+    use std::marker::PhantomData;
+    use std::path::Path;
+    use std::{mem, slice};
+
+    /// The elements of a binary matrix file, mapped into memory read-only.
+    ///
+    /// See [`Matrix::map_binary`].
+    pub struct MmapStorage<T> {
+        mmap: Mmap,
+        len: usize,
+        marker: PhantomData<T>,
+    }
+
+    impl<T: NpyElement> Storage<T> for MmapStorage<T> {
+        #[inline]
+        fn as_slice(&self) -> &[T] {
+            // SAFETY: `Matrix::map_binary` checked that the mapping holds
+            // `len` valid elements after the header, which is a multiple of
+            // their alignment, and mappings are aligned to pages.
+            unsafe { slice::from_raw_parts(self.mmap[HEADER_LEN..].as_ptr().cast(), self.len) }
+        }
+    }
+
+    impl<T> Matrix<T, MmapStorage<T>>
+    where
+        T: NpyElement,
+    {
+        /// Opens a file written by [`write_binary`] as a read-only matrix
+        /// that maps the file into memory instead of copying its elements.
+        ///
+        /// The checksum is verified when the file is opened, which reads it
+        /// once.
+        ///
+        /// # Errors
+        ///
+        /// Fails as [`read_binary`] does, and also returns
+        /// [`MatrixError::Format`] if the file is not in native byte order,
+        /// which [`read_binary`] can convert.
+        ///
+        /// # Safety
+        ///
+        /// The file must not be modified, by this or another process, while
+        /// the matrix is alive.
+        ///
+        /// [`write_binary`]: Matrix::write_binary
+        /// [`read_binary`]: Matrix::read_binary
+        pub unsafe fn map_binary<P: AsRef<Path>>(path: P) -> Result<Self, MatrixError> {
+            let file = File::open(path)?;
+            // SAFETY: the caller ensures that the file is not modified.
+            let mmap = unsafe { Mmap::map(&file)? };
+
+            let header_bytes = mmap
+                .get(..HEADER_LEN)
+                .ok_or_else(|| format_error("the file is truncated"))?;
+            let header = Header::parse::<T>(header_bytes.try_into().unwrap())?;
+            if header.swap {
+                return Err(format_error("the file is not in native byte order"));
+            }
+            let num_bytes = header.num_bytes::<T>()?;
+            let file_len = HEADER_LEN + num_bytes + FOOTER_LEN;
+            if mmap.len() != file_len {
+                let message = match mmap.len() < file_len {
+                    true => "the file is truncated",
+                    false => "the file has trailing bytes",
+                };
+                return Err(format_error(message));
+            }
+
+            let (contents, footer) = mmap.split_at(HEADER_LEN + num_bytes);
+            let mut crc = Crc32::new();
+            crc.update(contents);
+            check_crc(u32::from_le_bytes(footer.try_into().unwrap()), crc.finish())?;
+            if !T::is_valid(&contents[HEADER_LEN..]) {
+                return Err(format_error("the file holds invalid elements"));
+            }
+
+            let len = num_bytes / mem::size_of::<T>();
+            let storage = MmapStorage {
+                mmap,
+                len,
+                marker: PhantomData,
+            };
+            Ok(Self::from_layout(
+                header.num_rows,
+                header.num_columns,
+                storage,
+                header.layout,
+            ))
+        }
+    }
+}
+
+/// Reads exactly `buf.len()` bytes, failing with [`MatrixError::Format`]
+/// if the input ends first.
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<(), MatrixError> {
+    reader
+        .read_exact(buf)
+        .map_err(|error| truncated(error.into()))
+}
+
+/// Turns the error of an input that ended too early into a format error.
+fn truncated(error: MatrixError) -> MatrixError {
+    match error {
+        MatrixError::Io(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+            format_error("the file is truncated")
+        }
+        error => error,
+    }
+}
+
+fn check_crc(stored: u32, computed: u32) -> Result<(), MatrixError> {
+    if stored != computed {
+        let message =
+            format!("checksum mismatch: stored {stored:#010x}, computed {computed:#010x}");
+        return Err(format_error(message));
+    }
+    Ok(())
+}
+
+/// The CRC-32 of IEEE 802.3, as used by zip and PNG.
+struct Crc32(u32);
+
+impl Crc32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut k = 0;
+            while k < 8 {
+                crc = if crc & 1 == 1 {
+                    0xedb8_8320 ^ (crc >> 1)
+                } else {
+                    crc >> 1
+                };
+                k += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    const fn new() -> Self {
+        Self(!0)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = Self::TABLE[((self.0 ^ byte as u32) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    const fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+#[cfg(test)]
+mod test_binary {
+    use super::{Crc32, HEADER_LEN};
+    use crate::{Layout, Matrix, MatrixError, NpyElement};
+
+    fn write<T: NpyElement, S: crate::Storage<T>>(a: &Matrix<T, S>) -> Vec<u8> {
+        let mut file = Vec::new();
+        a.write_binary(&mut file).unwrap();
+        file
+    }
+
+    fn format_error<T: NpyElement + std::fmt::Debug>(file: &[u8]) -> String {
+        match Matrix::<T>::read_binary(file) {
+            Err(MatrixError::Format { message }) => message,
+            result => panic!("expected a format error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn crc32() {
+        let mut crc = Crc32::new();
+        crc.update(b"1234");
+        crc.update(b"56789");
+
+        assert_eq!(crc.finish(), 0xcbf4_3926);
+        assert_eq!(Crc32::new().finish(), 0);
+    }
+
+    #[test]
+    fn round_trip() {
+        let a: Matrix<f32> = Matrix::random_uniform(7, 5, -1., 1., 0);
+        let b = a.to_layout(Layout::ColumnMajor);
+        let file = write(&a);
+
+        assert_eq!(file.len(), HEADER_LEN + 35 * 4 + 4);
+        assert_eq!(Matrix::read_binary(&file[..]).unwrap(), a);
+        let c: Matrix<f32> = Matrix::read_binary(&write(&b)[..]).unwrap();
+        assert_eq!((c.layout(), &c), (Layout::ColumnMajor, &a));
+
+        let flags = Matrix::from([[true, false], [false, true]]);
+        assert_eq!(Matrix::read_binary(&write(&flags)[..]).unwrap(), flags);
+        let empty: Matrix<i64> = Matrix::zeros(0, 4);
+        assert_eq!(
+            Matrix::<i64>::read_binary(&write(&empty)[..])
+                .unwrap()
+                .shape(),
+            (0, 4)
+        );
+    }
+
+    #[test]
+    fn byte_swapped() {
+        let a = Matrix::from([[1i32, -2], [3, 4]]);
+        let mut file = write(&a);
+        file[13] ^= 1;
+        for element in file[HEADER_LEN..HEADER_LEN + 16].chunks_mut(4) {
+            element.reverse();
+        }
+        let mut crc = Crc32::new();
+        crc.update(&file[..HEADER_LEN + 16]);
+        let len = file.len();
+        file[len - 4..].copy_from_slice(&crc.finish().to_le_bytes());
+
+        assert_eq!(Matrix::read_binary(&file[..]).unwrap(), a);
+    }
+
+    #[test]
+    fn corrupted() {
+        let file = write(&Matrix::from([[1.5f64, 2.5, 3.5]]));
+
+        for i in 0..file.len() {
+            let mut corrupted = file.clone();
+            corrupted[i] ^= 0x10;
+            assert!(matches!(
+                Matrix::<f64>::read_binary(&corrupted[..]),
+                Err(MatrixError::Format { .. })
+            ));
+        }
+        for len in 0..file.len() {
+            assert_eq!(format_error::<f64>(&file[..len]), "the file is truncated");
+        }
+        assert!(format_error::<f64>(&file[..HEADER_LEN + 8]).contains("truncated"));
+        assert!(format_error::<f64>(&{
+            let mut file = file.clone();
+            file[70] ^= 1;
+            file
+        })
+        .starts_with("checksum mismatch"));
+        assert_eq!(
+            format_error::<f32>(&file),
+            "cannot read `f8` data into a matrix of `f32`"
+        );
+
+        let mut huge = file.clone();
+        huge[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(format_error::<f64>(&huge), "the matrix is too large");
+        huge[16..24].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        assert_eq!(format_error::<f64>(&huge), "the file is truncated");
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn mmap() {
+        use crate::MmapStorage;
+
+        let a: Matrix<f64> =
+            Matrix::random_normal(20, 10, 0., 1., 0).into_layout(Layout::ColumnMajor);
+        let path = std::env::temp_dir().join(format!("matrix-test-{}.bin", std::process::id()));
+        let file = write(&a);
+        std::fs::write(&path, &file).unwrap();
+
+        let b = unsafe { Matrix::<f64, MmapStorage<f64>>::map_binary(&path) }.unwrap();
+        assert_eq!(b, a);
+        assert_eq!(b.layout(), Layout::ColumnMajor);
+
+        std::fs::write(&path, &file[..file.len() - 1]).unwrap();
+        let error = unsafe { Matrix::<f64, MmapStorage<f64>>::map_binary(&path) }.err();
+        assert!(
+            matches!(error, Some(MatrixError::Format { message }) if message == "the file is truncated")
+        );
+
+        let mut corrupted = file.clone();
+        corrupted[HEADER_LEN] ^= 1;
+        std::fs::write(&path, &corrupted).unwrap();
+        let error = unsafe { Matrix::<f64, MmapStorage<f64>>::map_binary(&path) }.err();
+        assert!(
+            matches!(error, Some(MatrixError::Format { message }) if message.starts_with("checksum"))
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
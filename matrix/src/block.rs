@@ -0,0 +1,396 @@
+use super::{Matrix, Storage};
+
+impl<T> Matrix<T> {
+    /// Stacks `blocks` side by side, producing a matrix with the rows of
+    /// the blocks concatenated.
+    ///
+    /// Stacking no blocks produces an empty 0-by-0 matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the blocks do not all have the same number of rows.
+    #[track_caller]
+    pub fn hstack<S>(blocks: &[&Matrix<T, S>]) -> Self
+    where
+        T: Clone,
+        S: Storage<T>,
+    {
+        for (j, block) in blocks.iter().enumerate().skip(1) {
+            assert_dimension(
+                "num_rows",
+                (&[j], block.num_rows),
+                (&[0], blocks[0].num_rows),
+            );
+        }
+        assemble(&[blocks])
+    }
+
+    /// Stacks `blocks` one above the other, producing a matrix with the
+    /// columns of the blocks concatenated.
+    ///
+    /// Stacking no blocks produces an empty 0-by-0 matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the blocks do not all have the same number of columns.
+    #[track_caller]
+    pub fn vstack<S>(blocks: &[&Matrix<T, S>]) -> Self
+    where
+        T: Clone,
+        S: Storage<T>,
+    {
+        for (i, block) in blocks.iter().enumerate().skip(1) {
+            assert_dimension(
+                "num_columns",
+                (&[i], block.num_columns),
+                (&[0], blocks[0].num_columns),
+            );
+        }
+        let block_rows: Vec<&[&Matrix<T, S>]> = blocks.iter().map(std::slice::from_ref).collect();
+        assemble(&block_rows)
+    }
+
+    /// Assembles a block matrix from a grid of blocks, given row by row.
+    ///
+    /// The blocks in the same block row must have the same number of rows,
+    /// and the blocks in the same block column the same number of columns.
+    /// See also the [`block!`] macro.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block rows do not all have the same number of blocks,
+    /// or if the shapes of the blocks are not compatible.
+    ///
+    /// [`block!`]: crate::block
+    #[track_caller]
+    pub fn from_blocks<S>(blocks: &[&[&Matrix<T, S>]]) -> Self
+    where
+        T: Clone,
+        S: Storage<T>,
+    {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(i: usize, len: usize, expected: usize) -> ! {
+            panic!("`blocks[{i}].len()` (is {len}) should be equal to `blocks[0].len()` (is {expected})");
+        }
+
+        for (i, block_row) in blocks.iter().enumerate() {
+            if block_row.len() != blocks[0].len() {
+                assert_failed(i, block_row.len(), blocks[0].len());
+            }
+            for (j, block) in block_row.iter().enumerate() {
+                if j > 0 {
+                    assert_dimension(
+                        "num_rows",
+                        (&[i, j], block.num_rows),
+                        (&[i, 0], block_row[0].num_rows),
+                    );
+                }
+                if i > 0 {
+                    assert_dimension(
+                        "num_columns",
+                        (&[i, j], block.num_columns),
+                        (&[0, j], blocks[0][j].num_columns),
+                    );
+                }
+            }
+        }
+        assemble(blocks)
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: Storage<T>,
+{
+    /// Splits the matrix into horizontal bands, before each of the row
+    /// `indices`, and returns the `indices.len() + 1` bands from top to
+    /// bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indices` is not sorted in nondecreasing order, or if an
+    /// index is larger than `self.num_rows()`.
+    #[track_caller]
+    pub fn split_rows(&self, indices: &[usize]) -> Vec<Matrix<T>>
+    where
+        T: Clone,
+    {
+        bounds(indices, self.num_rows, "num_rows")
+            .map(|(start, end)| {
+                let data = (start..end)
+                    .flat_map(|i| (0..self.num_columns).map(move |j| self.element(i, j).clone()))
+                    .collect();
+                Matrix::from_row_major(end - start, self.num_columns, data)
+            })
+            .collect()
+    }
+
+    /// Splits the matrix into vertical bands, before each of the column
+    /// `indices`, and returns the `indices.len() + 1` bands from left to
+    /// right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indices` is not sorted in nondecreasing order, or if an
+    /// index is larger than `self.num_columns()`.
+    #[track_caller]
+    pub fn split_columns(&self, indices: &[usize]) -> Vec<Matrix<T>>
+    where
+        T: Clone,
+    {
+        bounds(indices, self.num_columns, "num_columns")
+            .map(|(start, end)| {
+                let data = (0..self.num_rows)
+                    .flat_map(|i| (start..end).map(move |j| self.element(i, j).clone()))
+                    .collect();
+                Matrix::from_row_major(self.num_rows, end - start, data)
+            })
+            .collect()
+    }
+
+    /// Returns the element `(i, j)`, which is known to be in bounds.
+    #[inline]
+    fn element(&self, i: usize, j: usize) -> &T {
+        &self.data.as_slice()[self.offset(i, j)]
+    }
+}
+
+/// Copies the elements of the grid of `blocks`, whose shapes are
+/// compatible, into a row-major matrix.
+fn assemble<T, S>(blocks: &[&[&Matrix<T, S>]]) -> Matrix<T>
+where
+    T: Clone,
+    S: Storage<T>,
+{
+    let num_rows = blocks
+        .iter()
+        .map(|block_row| block_row.first().map_or(0, |block| block.num_rows))
+        .sum();
+    let num_columns = blocks.first().map_or(0, |block_row| {
+        block_row.iter().map(|block| block.num_columns).sum()
+    });
+
+    let mut data = Vec::with_capacity(num_rows * num_columns);
+    for block_row in blocks {
+        for i in 0..block_row.first().map_or(0, |block| block.num_rows) {
+            for block in block_row.iter() {
+                data.extend((0..block.num_columns).map(|j| block.element(i, j).clone()));
+            }
+        }
+    }
+    Matrix::from_row_major(num_rows, num_columns, data)
+}
+
+/// Returns the `(start, end)` bounds of the bands between consecutive
+/// split `indices`, from zero to `len`.
+#[track_caller]
+fn bounds<'a>(
+    indices: &'a [usize],
+    len: usize,
+    len_name: &'a str,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_sorted_failed(k: usize, index: usize, previous: usize) -> ! {
+        panic!(
+            "`indices[{k}]` (is {index}) should be >= `indices[{}]` (is {previous})",
+            k - 1
+        );
+    }
+
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_in_bounds_failed(k: usize, index: usize, len_name: &str, len: usize) -> ! {
+        panic!("`indices[{k}]` (is {index}) should be <= `{len_name}` (is {len})");
+    }
+
+    for (k, &index) in indices.iter().enumerate() {
+        if k > 0 && index < indices[k - 1] {
+            assert_sorted_failed(k, index, indices[k - 1]);
+        }
+        if index > len {
+            assert_in_bounds_failed(k, index, len_name, len);
+        }
+    }
+    let starts = std::iter::once(0).chain(indices.iter().copied());
+    let ends = indices.iter().copied().chain(std::iter::once(len));
+    starts.zip(ends)
+}
+
+/// Panics, naming both blocks, if the dimension `dimension_name` of the
+/// block at `index` in `blocks` differs from the one of the block at
+/// `expected_index`, which it must match.
+#[inline]
+#[track_caller]
+fn assert_dimension(
+    dimension_name: &str,
+    (index, dimension): (&[usize], usize),
+    (expected_index, expected): (&[usize], usize),
+) {
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_failed(
+        dimension_name: &str,
+        (index, dimension): (&[usize], usize),
+        (expected_index, expected): (&[usize], usize),
+    ) -> ! {
+        let block =
+            |index: &[usize]| -> String { index.iter().map(|k| format!("[{k}]")).collect() };
+        panic!(
+            "`blocks{}.{dimension_name}()` (is {dimension}) \
+            should be equal to `blocks{}.{dimension_name}()` (is {expected})",
+            block(index),
+            block(expected_index)
+        );
+    }
+
+    if dimension != expected {
+        assert_failed(
+            dimension_name,
+            (index, dimension),
+            (expected_index, expected),
+        );
+    }
+}
+
+/// Assembles a block matrix from a grid of blocks, given row by row.
+///
+/// `block![[a, b], [c, d]]` borrows the four blocks and is equivalent to
+/// `Matrix::from_blocks(&[&[&a, &b], &[&c, &d]])`. See
+/// [`Matrix::from_blocks`].
+///
+/// # Examples
+///
+/// ```
+/// use matrix::{block, Matrix};
+///
+/// let a: Matrix<f64> = Matrix::from([[2., 1.], [1., 2.]]);
+/// let b = Matrix::from([[1.], [1.]]);
+/// let c = Matrix::from([[1., 1.]]);
+///
+/// let kkt = block![[a, b], [c, Matrix::zeros(1, 1)]];
+///
+/// assert_eq!(kkt, Matrix::from([[2., 1., 1.], [1., 2., 1.], [1., 1., 0.]]));
+/// ```
+#[macro_export]
+macro_rules! block {
+    ($([$($block:expr),* $(,)?]),* $(,)?) => {
+        $crate::Matrix::from_blocks(&[$(&[$(&$block),*][..]),*])
+    };
+}
+
+#[cfg(test)]
+mod test_block {
+    use crate::{Layout, Matrix};
+
+    #[test]
+    fn hstack() {
+        let a = Matrix::from([[0, 1], [4, 5]]);
+        let b = Matrix::from([[2], [6]]).into_layout(Layout::ColumnMajor);
+        let c = Matrix::from([[3], [7]]);
+
+        assert_eq!(
+            Matrix::hstack(&[&a, &b, &c]),
+            Matrix::from([[0, 1, 2, 3], [4, 5, 6, 7]])
+        );
+        assert_eq!(Matrix::<i32>::hstack::<Vec<i32>>(&[]).shape(), (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "`blocks[1].num_rows()` (is 1) \
+                    should be equal to `blocks[0].num_rows()` (is 2)")]
+    fn hstack_with_incompatible_shapes() {
+        let _ = Matrix::hstack(&[&Matrix::from([[0], [1]]), &Matrix::from([[2]])]);
+    }
+
+    #[test]
+    fn vstack() {
+        let a = Matrix::from([[0, 1]]);
+        let b = Matrix::from([[2, 3], [4, 5]]);
+        let empty = Matrix::zeros(0, 2);
+
+        assert_eq!(
+            Matrix::vstack(&[&a, &empty, &b]),
+            Matrix::from([[0, 1], [2, 3], [4, 5]])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`blocks[2].num_columns()` (is 1) \
+                    should be equal to `blocks[0].num_columns()` (is 2)")]
+    fn vstack_with_incompatible_shapes() {
+        let a = Matrix::from([[0, 1]]);
+        let _ = Matrix::vstack(&[&a, &a, &Matrix::from([[2]])]);
+    }
+
+    #[test]
+    fn block() {
+        let a = Matrix::from([[1, 2], [3, 4]]);
+        let b = Matrix::from([[5], [6]]);
+        let matrix = block![[a, b], [Matrix::zeros(1, 2), Matrix::ones(1, 1)]];
+
+        assert_eq!(matrix, Matrix::from([[1, 2, 5], [3, 4, 6], [0, 0, 1]]));
+        assert_eq!(block![[a]], a);
+        assert_eq!(Matrix::<i32>::from_blocks::<Vec<i32>>(&[]).shape(), (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "`blocks[1][1].num_columns()` (is 2) \
+                    should be equal to `blocks[0][1].num_columns()` (is 1)")]
+    fn block_with_incompatible_columns() {
+        let a = Matrix::from([[1, 2], [3, 4]]);
+        let b = Matrix::from([[5], [6]]);
+        let _ = block![[a, b], [a, a]];
+    }
+
+    #[test]
+    #[should_panic(expected = "`blocks[0][1].num_rows()` (is 1) \
+                    should be equal to `blocks[0][0].num_rows()` (is 2)")]
+    fn block_with_incompatible_rows() {
+        let a = Matrix::from([[1, 2], [3, 4]]);
+        let _ = block![[a, Matrix::from([[5]])]];
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`blocks[1].len()` (is 1) should be equal to `blocks[0].len()` (is 2)"
+    )]
+    fn block_with_ragged_rows() {
+        let a = Matrix::from([[1]]);
+        let _ = block![[a, a], [a]];
+    }
+
+    #[test]
+    fn split() {
+        let matrix = Matrix::from([[0, 1, 2], [3, 4, 5], [6, 7, 8]]);
+
+        for matrix in [matrix.clone(), matrix.to_layout(Layout::ColumnMajor)] {
+            let [top, bottom]: [Matrix<i32>; 2] = matrix.split_rows(&[1]).try_into().unwrap();
+            assert_eq!(top, Matrix::from([[0, 1, 2]]));
+            assert_eq!(bottom, Matrix::from([[3, 4, 5], [6, 7, 8]]));
+
+            let bands = matrix.split_columns(&[0, 2]);
+            assert_eq!(bands[0].shape(), (3, 0));
+            assert_eq!(bands[1], Matrix::from([[0, 1], [3, 4], [6, 7]]));
+            assert_eq!(bands[2], Matrix::from([[2], [5], [8]]));
+            assert_eq!(Matrix::hstack(&bands.iter().collect::<Vec<_>>()), matrix);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "`indices[1]` (is 1) should be >= `indices[0]` (is 2)")]
+    fn split_with_unsorted_indices() {
+        let _ = Matrix::from([[0, 1, 2]]).split_columns(&[2, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`indices[0]` (is 2) should be <= `num_rows` (is 1)")]
+    fn split_out_of_bounds() {
+        let _ = Matrix::from([[0, 1, 2]]).split_rows(&[2]);
+    }
+}
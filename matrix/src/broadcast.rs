@@ -0,0 +1,425 @@
+use super::{Layout, Matrix, Storage};
+use std::ops::{Add, Div, Mul, Sub};
+
+/// A read-only view of a matrix that is repeated along each axis where it
+/// has length one, as in NumPy.
+///
+/// Arithmetic between a [`Matrix`] and a `Broadcast`, or between two
+/// `Broadcast`s, is element-wise. The operands are first broadcast to a
+/// common shape: along each axis, their lengths must be equal, or one of
+/// them must be one, in which case that operand is repeated along the
+/// axis. So a 1-by-`n` row is added to every row of an `m`-by-`n` matrix,
+/// and a 1-by-`n` row and an `m`-by-1 column produce an `m`-by-`n` matrix.
+///
+/// # Examples
+///
+/// ```
+/// use matrix::{Broadcast, Matrix};
+///
+/// let a = Matrix::from([[1., 2.], [3., 4.]]);
+///
+/// assert_eq!(&a - Broadcast::row(&[1., 2.]), Matrix::from([[0., 0.], [2., 2.]]));
+/// assert_eq!(&a * Broadcast::column(&[10., 100.]), Matrix::from([[10., 20.], [300., 400.]]));
+/// assert_eq!(&a / Broadcast::scalar(&2.), Matrix::from([[0.5, 1.], [1.5, 2.]]));
+/// ```
+#[derive(Debug)]
+pub struct Broadcast<'a, T> {
+    data: &'a [T],
+    num_rows: usize,
+    num_columns: usize,
+    row_stride: usize,
+    column_stride: usize,
+}
+
+impl<T> Clone for Broadcast<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Broadcast<'_, T> {}
+
+impl<'a, T> Broadcast<'a, T> {
+    /// Creates a 1-by-`row.len()` view of `row`, which is repeated down the
+    /// rows of the other operand.
+    pub fn row(row: &'a [T]) -> Self {
+        Self {
+            data: row,
+            num_rows: 1,
+            num_columns: row.len(),
+            row_stride: 0,
+            column_stride: 1,
+        }
+    }
+
+    /// Creates a `column.len()`-by-1 view of `column`, which is repeated
+    /// across the columns of the other operand.
+    pub fn column(column: &'a [T]) -> Self {
+        Self {
+            data: column,
+            num_rows: column.len(),
+            num_columns: 1,
+            row_stride: 1,
+            column_stride: 0,
+        }
+    }
+
+    /// Creates a 1-by-1 view of `value`, which is repeated along both axes.
+    pub fn scalar(value: &'a T) -> Self {
+        Self::row(std::slice::from_ref(value))
+    }
+
+    /// Returns the shape of the view before broadcasting.
+    #[inline]
+    pub fn shape(&self) -> (usize, usize) {
+        (self.num_rows, self.num_columns)
+    }
+
+    /// Returns the element `(i, j)` of the view broadcast to any shape
+    /// compatible with its own.
+    #[inline]
+    fn get(&self, i: usize, j: usize) -> &'a T {
+        let i = if self.num_rows == 1 { 0 } else { i };
+        let j = if self.num_columns == 1 { 0 } else { j };
+        &self.data[i * self.row_stride + j * self.column_stride]
+    }
+
+    /// Applies `f` to the elements of `self` and `other`, broadcast to their
+    /// common shape, producing a row-major matrix.
+    #[track_caller]
+    fn zip_with<U, F>(self, other: Broadcast<'_, T>, f: F) -> Matrix<U>
+    where
+        F: Fn(&T, &T) -> U,
+    {
+        let num_rows = broadcast_len("rows", "num_rows", self.num_rows, other.num_rows);
+        let num_columns = broadcast_len(
+            "columns",
+            "num_columns",
+            self.num_columns,
+            other.num_columns,
+        );
+        let data = (0..num_rows)
+            .flat_map(|i| (0..num_columns).map(move |j| (i, j)))
+            .map(|(i, j)| f(self.get(i, j), other.get(i, j)))
+            .collect();
+        Matrix::from_row_major(num_rows, num_columns, data)
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: Storage<T>,
+{
+    /// Returns a [`Broadcast`] view of the matrix, which is repeated along
+    /// each axis where it has length one.
+    pub fn broadcast(&self) -> Broadcast<'_, T> {
+        let (row_stride, column_stride) = match self.layout {
+            Layout::RowMajor => (self.leading_dimension, 1),
+            Layout::ColumnMajor => (1, self.leading_dimension),
+        };
+        Broadcast {
+            data: self.data.as_slice(),
+            num_rows: self.num_rows,
+            num_columns: self.num_columns,
+            row_stride,
+            column_stride,
+        }
+    }
+
+    /// Adds `row` to every row of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row.len()` is neither `num_columns` nor one.
+    #[track_caller]
+    pub fn add_row_vector(&self, row: &[T]) -> Matrix<T>
+    where
+        T: Clone + Add<Output = T>,
+    {
+        self + Broadcast::row(row)
+    }
+
+    /// Subtracts `row` from every row of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row.len()` is neither `num_columns` nor one.
+    #[track_caller]
+    pub fn sub_row_vector(&self, row: &[T]) -> Matrix<T>
+    where
+        T: Clone + Sub<Output = T>,
+    {
+        self - Broadcast::row(row)
+    }
+
+    /// Adds `column` to every column of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column.len()` is neither `num_rows` nor one.
+    #[track_caller]
+    pub fn add_column_vector(&self, column: &[T]) -> Matrix<T>
+    where
+        T: Clone + Add<Output = T>,
+    {
+        self + Broadcast::column(column)
+    }
+
+    /// Subtracts `column` from every column of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column.len()` is neither `num_rows` nor one.
+    #[track_caller]
+    pub fn sub_column_vector(&self, column: &[T]) -> Matrix<T>
+    where
+        T: Clone + Sub<Output = T>,
+    {
+        self - Broadcast::column(column)
+    }
+
+    /// Multiplies every row of the matrix, element by element, by `row`,
+    /// that is, scales column `j` by `row[j]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row.len()` is neither `num_columns` nor one.
+    #[track_caller]
+    pub fn mul_row_broadcast(&self, row: &[T]) -> Matrix<T>
+    where
+        T: Clone + Mul<Output = T>,
+    {
+        self * Broadcast::row(row)
+    }
+
+    /// Multiplies every column of the matrix, element by element, by
+    /// `column`, that is, scales row `i` by `column[i]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column.len()` is neither `num_rows` nor one.
+    #[track_caller]
+    pub fn mul_column_broadcast(&self, column: &[T]) -> Matrix<T>
+    where
+        T: Clone + Mul<Output = T>,
+    {
+        self * Broadcast::column(column)
+    }
+
+    /// Divides every row of the matrix, element by element, by `row`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row.len()` is neither `num_columns` nor one.
+    #[track_caller]
+    pub fn div_row_broadcast(&self, row: &[T]) -> Matrix<T>
+    where
+        T: Clone + Div<Output = T>,
+    {
+        self / Broadcast::row(row)
+    }
+
+    /// Divides every column of the matrix, element by element, by
+    /// `column`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column.len()` is neither `num_rows` nor one.
+    #[track_caller]
+    pub fn div_column_broadcast(&self, column: &[T]) -> Matrix<T>
+    where
+        T: Clone + Div<Output = T>,
+    {
+        self / Broadcast::column(column)
+    }
+}
+
+/// Implements an element-wise operator between `Broadcast`s and between a
+/// `&Matrix` and a `Broadcast`.
+macro_rules! impl_broadcast_op {
+    ($Op:ident, $op:ident) => {
+        impl<'a, 'b, T> $Op<Broadcast<'b, T>> for Broadcast<'a, T>
+        where
+            T: Clone + $Op<Output = T>,
+        {
+            type Output = Matrix<T>;
+
+            /// # Panics
+            ///
+            /// Panics if the operands cannot be broadcast to a common shape.
+            #[track_caller]
+            fn $op(self, rhs: Broadcast<'b, T>) -> Matrix<T> {
+                self.zip_with(rhs, |x, y| $Op::$op(x.clone(), y.clone()))
+            }
+        }
+
+        impl<'a, T, S> $Op<Broadcast<'a, T>> for &Matrix<T, S>
+        where
+            T: Clone + $Op<Output = T>,
+            S: Storage<T>,
+        {
+            type Output = Matrix<T>;
+
+            /// # Panics
+            ///
+            /// Panics if the operands cannot be broadcast to a common shape.
+            #[track_caller]
+            fn $op(self, rhs: Broadcast<'a, T>) -> Matrix<T> {
+                $Op::$op(self.broadcast(), rhs)
+            }
+        }
+    };
+}
+
+impl_broadcast_op!(Add, add);
+impl_broadcast_op!(Sub, sub);
+impl_broadcast_op!(Mul, mul);
+impl_broadcast_op!(Div, div);
+
+/// Implements an element-wise operator between two `&Matrix`s, which are
+/// broadcast to a common shape.
+///
+/// `*` is deliberately left out: between two matrices it would be easy to
+/// mistake for [`Matrix::mul`]. Use [`Matrix::hadamard`] or a `Broadcast`
+/// operand instead.
+macro_rules! impl_matrix_op {
+    ($Op:ident, $op:ident) => {
+        impl<T, SA, SB> $Op<&Matrix<T, SB>> for &Matrix<T, SA>
+        where
+            T: Clone + $Op<Output = T>,
+            SA: Storage<T>,
+            SB: Storage<T>,
+        {
+            type Output = Matrix<T>;
+
+            /// # Panics
+            ///
+            /// Panics if the operands cannot be broadcast to a common shape.
+            #[track_caller]
+            fn $op(self, rhs: &Matrix<T, SB>) -> Matrix<T> {
+                $Op::$op(self.broadcast(), rhs.broadcast())
+            }
+        }
+    };
+}
+
+impl_matrix_op!(Add, add);
+impl_matrix_op!(Sub, sub);
+
+/// Returns the length of the broadcast of two operands of lengths `lhs`
+/// and `rhs` along the axis `axis`.
+#[track_caller]
+fn broadcast_len(axis: &str, name: &str, lhs: usize, rhs: usize) -> usize {
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_failed(axis: &str, name: &str, lhs: usize, rhs: usize) -> ! {
+        panic!(
+            "cannot broadcast along the {axis}: `lhs.{name}()` (is {lhs}) \
+            should be equal to `rhs.{name}()` (is {rhs}), or either should be 1"
+        );
+    }
+
+    match (lhs, rhs) {
+        _ if lhs == rhs => lhs,
+        (1, _) => rhs,
+        (_, 1) => lhs,
+        _ => assert_failed(axis, name, lhs, rhs),
+    }
+}
+
+#[cfg(test)]
+mod test_broadcast {
+    use crate::{Broadcast, Layout, Matrix};
+
+    #[test]
+    fn vectors() {
+        let a = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+
+        assert_eq!(
+            a.add_row_vector(&[1, 0, -1]),
+            Matrix::from([[2, 2, 2], [5, 5, 5]])
+        );
+        assert_eq!(
+            a.sub_row_vector(&[1, 2, 3]),
+            Matrix::from([[0, 0, 0], [3, 3, 3]])
+        );
+        assert_eq!(
+            a.add_column_vector(&[10, 20]),
+            Matrix::from([[11, 12, 13], [24, 25, 26]])
+        );
+        assert_eq!(
+            a.sub_column_vector(&[1, 4]),
+            Matrix::from([[0, 1, 2], [0, 1, 2]])
+        );
+        assert_eq!(
+            a.mul_row_broadcast(&[1, 0, 2]),
+            Matrix::from([[1, 0, 6], [4, 0, 12]])
+        );
+        assert_eq!(
+            a.mul_column_broadcast(&[-1, 2]),
+            Matrix::from([[-1, -2, -3], [8, 10, 12]])
+        );
+        assert_eq!(
+            a.div_row_broadcast(&[1, 2, 3]),
+            Matrix::from([[1, 1, 1], [4, 2, 2]])
+        );
+        assert_eq!(
+            a.div_column_broadcast(&[1, 2]),
+            Matrix::from([[1, 2, 3], [2, 2, 3]])
+        );
+        assert_eq!(a.add_row_vector(&[1]), a.add_column_vector(&[1]));
+    }
+
+    #[test]
+    fn matrices() {
+        let a = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+        let row = Matrix::from([[1, 1, 1]]);
+        let column = Matrix::from([[1], [2]]);
+
+        assert_eq!(&a - &a, Matrix::zeros(2, 3));
+        assert_eq!(&a - &row, Matrix::from([[0, 1, 2], [3, 4, 5]]));
+        assert_eq!(&row + &column, Matrix::from([[2, 2, 2], [3, 3, 3]]));
+        assert_eq!(&column + &row, &row + &column);
+
+        let b = a.to_layout(Layout::ColumnMajor);
+        assert_eq!(&a + &b, Matrix::from([[2, 4, 6], [8, 10, 12]]));
+
+        let c = Matrix::with_padding(2, 3, 5, 1);
+        assert_eq!(&a * c.broadcast(), a);
+    }
+
+    #[test]
+    fn centering() {
+        let data: Matrix<f64> = Matrix::from([[1., 10.], [3., 30.]]);
+        let mean = data.mean_axis(crate::Axis::Columns);
+        let centered = data.sub_row_vector(&mean);
+
+        assert_eq!(centered, Matrix::from([[-1., -10.], [1., 10.]]));
+        assert_eq!(&data - Broadcast::row(&mean), centered);
+    }
+
+    #[test]
+    fn empty() {
+        let a: Matrix<i32> = Matrix::zeros(0, 3);
+
+        assert_eq!(a.add_row_vector(&[1, 2, 3]).shape(), (0, 3));
+        assert_eq!((&a + Broadcast::column(&[1])).shape(), (0, 3));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "cannot broadcast along the columns: `lhs.num_columns()` (is 3) \
+                               should be equal to `rhs.num_columns()` (is 2), or either should be 1"
+    )]
+    fn incompatible_row() {
+        Matrix::<i32>::zeros(2, 3).add_row_vector(&[1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot broadcast along the rows: `lhs.num_rows()` (is 2) \
+                               should be equal to `rhs.num_rows()` (is 3), or either should be 1")]
+    fn incompatible_matrices() {
+        let _ = &Matrix::<i32>::zeros(2, 3) - &Matrix::zeros(3, 3);
+    }
+}
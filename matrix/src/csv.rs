@@ -0,0 +1,487 @@
+use super::{Matrix, MatrixError, Storage};
+use std::fmt::{self, Write as _};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::mem;
+use std::str::FromStr;
+
+/// What to do with an empty field when reading a CSV file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Missing<T> {
+    /// Fails with [`MatrixError::Parse`].
+    Error,
+    /// Uses the given value in place of the field.
+    Fill(T),
+}
+
+/// Options of [`Matrix::read_csv`] and [`Matrix::write_csv`].
+///
+/// The defaults read and write comma-separated values, without a header
+/// or comments, quoted with `"` where needed, and with empty fields being
+/// an error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CsvOptions<T> {
+    /// Separates the fields of a record.
+    pub delimiter: char,
+    /// Whether the first record is a header, skipped when reading.
+    pub header: bool,
+    /// Starts the lines that are skipped when reading, if any.
+    pub comment: Option<char>,
+    /// What to do with empty fields when reading.
+    pub missing: Missing<T>,
+    /// Encloses the fields that contain the delimiter, the quote or a line
+    /// break, or that start or end with whitespace, if any. A quote inside
+    /// a quoted field is written twice.
+    pub quote: Option<char>,
+}
+
+impl<T> CsvOptions<T> {
+    /// Returns the default options, with tabs as delimiters.
+    pub fn tsv() -> Self {
+        Self {
+            delimiter: '\t',
+            ..Self::default()
+        }
+    }
+}
+
+impl<T> Default for CsvOptions<T> {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            header: false,
+            comment: None,
+            missing: Missing::Error,
+            quote: Some('"'),
+        }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Clone + FromStr,
+    T::Err: fmt::Display,
+{
+    /// Reads a matrix from CSV data, one row per record.
+    ///
+    /// Blank lines are skipped, and the surrounding whitespace of each field
+    /// is ignored, except inside quotes. An empty input gives an empty
+    /// matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Parse`] if a field cannot be parsed, if a
+    /// record does not have as many fields as the first one, or if a
+    /// quoted field is not closed, and [`MatrixError::Io`] if reading
+    /// fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matrix::{CsvOptions, Matrix};
+    ///
+    /// let csv = "x,y\n1,2\n3,4\n";
+    /// let options = CsvOptions { header: true, ..CsvOptions::default() };
+    /// let a: Matrix<i32> = Matrix::read_csv(csv.as_bytes(), &options)?;
+    ///
+    /// assert_eq!(a, Matrix::from([[1, 2], [3, 4]]));
+    /// # Ok::<(), matrix::MatrixError>(())
+    /// ```
+    pub fn read_csv<R: Read>(reader: R, options: &CsvOptions<T>) -> Result<Self, MatrixError> {
+        let mut data = Vec::new();
+        let mut num_rows = 0;
+        let num_columns = Self::read_csv_rows(reader, options, |row| {
+            data.extend_from_slice(row);
+            num_rows += 1;
+        })?;
+        Ok(Self::from_row_major(num_rows, num_columns, data))
+    }
+
+    /// Reads CSV data as [`read_csv`] does, but passes each row to `f`
+    /// instead of collecting them, so that inputs larger than memory can be
+    /// processed. Returns the number of columns, or 0 for an empty input.
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`read_csv`] does, after passing the rows before the error
+    /// to `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matrix::{CsvOptions, Matrix};
+    ///
+    /// let csv = "# totals\n1.5\t2\n3\t\n";
+    /// let mut sums = Vec::new();
+    /// Matrix::<f64>::read_csv_rows(csv.as_bytes(), &CsvOptions {
+    ///     comment: Some('#'),
+    ///     missing: matrix::Missing::Fill(0.),
+    ///     ..CsvOptions::tsv()
+    /// }, |row| sums.push(row.iter().sum::<f64>()))?;
+    ///
+    /// assert_eq!(sums, [3.5, 3.]);
+    /// # Ok::<(), matrix::MatrixError>(())
+    /// ```
+    ///
+    /// [`read_csv`]: Matrix::read_csv
+    pub fn read_csv_rows<R, F>(
+        reader: R,
+        options: &CsvOptions<T>,
+        mut f: F,
+    ) -> Result<usize, MatrixError>
+    where
+        R: Read,
+        F: FnMut(&[T]),
+    {
+        let mut records = Records {
+            reader: BufReader::new(reader),
+            options,
+            line: 0,
+            buffer: String::new(),
+        };
+        let mut fields = Vec::new();
+        let mut row = Vec::new();
+        if options.header {
+            records.next(&mut fields)?;
+        }
+
+        let mut num_columns = None;
+        while let Some(line) = records.next(&mut fields)? {
+            let num_columns = *num_columns.get_or_insert(fields.len());
+            if fields.len() != num_columns {
+                return Err(MatrixError::Parse {
+                    line,
+                    column: fields.len().min(num_columns) + 1,
+                    message: format!("expected {num_columns} fields, found {}", fields.len()),
+                });
+            }
+
+            row.clear();
+            for (j, field) in fields.iter().enumerate() {
+                let value = match (&options.missing, field.is_empty()) {
+                    (Missing::Fill(value), true) => Ok(value.clone()),
+                    (Missing::Error, true) => Err("missing value".to_string()),
+                    (_, false) => field
+                        .parse()
+                        .map_err(|error| format!("cannot parse {field:?}: {error}")),
+                }
+                .map_err(|message| MatrixError::Parse {
+                    line,
+                    column: j + 1,
+                    message,
+                })?;
+                row.push(value);
+            }
+            f(&row);
+        }
+        Ok(num_columns.unwrap_or(0))
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    T: fmt::Display,
+    S: Storage<T>,
+{
+    /// Writes the matrix as CSV data, one record per row, with each element
+    /// formatted by its [`Display`] implementation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Io`] if writing fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matrix::{CsvOptions, Matrix};
+    ///
+    /// let a = Matrix::from([[1.5, -2.], [0., 1e-3]]);
+    /// let mut csv = Vec::new();
+    /// a.write_csv(&mut csv, &CsvOptions::default())?;
+    ///
+    /// assert_eq!(csv, b"1.5,-2\n0,0.001\n");
+    /// # Ok::<(), matrix::MatrixError>(())
+    /// ```
+    ///
+    /// [`Display`]: fmt::Display
+    pub fn write_csv<W: Write>(
+        &self,
+        writer: W,
+        options: &CsvOptions<T>,
+    ) -> Result<(), MatrixError> {
+        let mut writer = BufWriter::new(writer);
+        let mut field = String::new();
+        for i in 0..self.num_rows {
+            for j in 0..self.num_columns {
+                if j > 0 {
+                    write!(writer, "{}", options.delimiter)?;
+                }
+                field.clear();
+                write!(field, "{}", self[(i, j)])
+                    .expect("a Display implementation returned an error");
+                let needs_quotes = |quote| {
+                    field.contains([options.delimiter, quote, '\n', '\r'])
+                        || field.starts_with(char::is_whitespace)
+                        || field.ends_with(char::is_whitespace)
+                };
+                match options.quote {
+                    Some(quote) if needs_quotes(quote) => {
+                        let escaped = field.replace(quote, &format!("{quote}{quote}"));
+                        write!(writer, "{quote}{escaped}{quote}")?;
+                    }
+                    _ => writer.write_all(field.as_bytes())?,
+                }
+            }
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Takes a field out of `field`, without its surrounding whitespace, but
+/// keeping whatever was between its quotes, which end at `closed_at`.
+fn end_field(field: &mut String, closed_at: Option<usize>) -> String {
+    let field = mem::take(field);
+    match closed_at {
+        Some(end) => {
+            let (quoted, rest) = field.split_at(end);
+            quoted.to_string() + rest.trim_end()
+        }
+        None => field.trim().to_string(),
+    }
+}
+
+/// Splits CSV data into records.
+struct Records<'a, R, T> {
+    reader: R,
+    options: &'a CsvOptions<T>,
+    line: usize,
+    buffer: String,
+}
+
+impl<R: BufRead, T> Records<'_, R, T> {
+    /// Reads the next record into `fields`, skipping blank and comment
+    /// lines, and returns the number of its first line, or `None` at the
+    /// end of the input.
+    fn next(&mut self, fields: &mut Vec<String>) -> Result<Option<usize>, MatrixError> {
+        loop {
+            if !self.read_line()? {
+                return Ok(None);
+            }
+            // The delimiter may be whitespace, such as a tab, which must not
+            // be trimmed: a line of delimiters is a record of empty fields.
+            let delimiter = self.options.delimiter;
+            let text = self
+                .buffer
+                .trim_matches(|c: char| c.is_whitespace() && c != delimiter);
+            let is_comment = matches!(self.options.comment, Some(c) if text.starts_with(c));
+            if !text.is_empty() && !is_comment {
+                break;
+            }
+        }
+
+        let line = self.line;
+        let (delimiter, quote) = (self.options.delimiter, self.options.quote);
+        let mut field = String::new();
+        let mut quoted = false;
+        // The length of the field at its closing quote, if it was quoted.
+        let mut closed_at = None;
+        fields.clear();
+        loop {
+            let mut chars = self
+                .buffer
+                .trim_end_matches(['\n', '\r'])
+                .chars()
+                .peekable();
+            while let Some(c) = chars.next() {
+                if quoted {
+                    // A doubled quote stands for itself.
+                    if Some(c) != quote || chars.next_if_eq(&c).is_some() {
+                        field.push(c);
+                    } else {
+                        quoted = false;
+                        closed_at = Some(field.len());
+                    }
+                } else if Some(c) == quote && field.trim().is_empty() {
+                    field.clear();
+                    quoted = true;
+                } else if c == delimiter {
+                    fields.push(end_field(&mut field, closed_at.take()));
+                } else {
+                    field.push(c);
+                }
+            }
+            if !quoted {
+                break;
+            }
+
+            // The quoted field goes on in the next line.
+            field.push('\n');
+            if !self.read_line()? {
+                return Err(MatrixError::Parse {
+                    line,
+                    column: fields.len() + 1,
+                    message: "unterminated quoted field".to_string(),
+                });
+            }
+        }
+        fields.push(end_field(&mut field, closed_at));
+        Ok(Some(line))
+    }
+
+    /// Reads the next line into the buffer, or returns `false` at the end
+    /// of the input.
+    fn read_line(&mut self) -> Result<bool, MatrixError> {
+        self.buffer.clear();
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            return Ok(false);
+        }
+        self.line += 1;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test_csv {
+    use crate::{CsvOptions, Layout, Matrix, MatrixError, Missing};
+
+    fn parse_error(csv: &str, options: &CsvOptions<f64>) -> (usize, usize, String) {
+        match Matrix::read_csv(csv.as_bytes(), options) {
+            Err(MatrixError::Parse {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            result => panic!("expected a parse error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn read() {
+        let csv = "a,b,c\r\n\n# comment\n 1, 2.5 ,-3\n\"4\",5,\"6\"\n";
+        let options = CsvOptions {
+            header: true,
+            comment: Some('#'),
+            ..CsvOptions::default()
+        };
+        let a: Matrix<f64> = Matrix::read_csv(csv.as_bytes(), &options).unwrap();
+
+        assert_eq!(a, Matrix::from([[1., 2.5, -3.], [4., 5., 6.]]));
+        assert_eq!(
+            Matrix::<f64>::read_csv(&b""[..], &CsvOptions::default())
+                .unwrap()
+                .shape(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn missing() {
+        let csv = "1\t\t3\n\t5\t6\n";
+        let options = CsvOptions {
+            missing: Missing::Fill(f64::NAN),
+            ..CsvOptions::tsv()
+        };
+        let a: Matrix<f64> = Matrix::read_csv(csv.as_bytes(), &options).unwrap();
+
+        assert_eq!(
+            a.map(|x| x.is_nan()),
+            Matrix::from([[false, true, false], [true, false, false]])
+        );
+        assert_eq!(
+            parse_error(csv, &CsvOptions::tsv()),
+            (1, 2, "missing value".to_string())
+        );
+
+        let b: Matrix<f64> = Matrix::read_csv("1\t2\n\t\n3\t4\n".as_bytes(), &options).unwrap();
+        assert_eq!(b.shape(), (3, 2));
+        assert!(b[1].iter().all(|x| x.is_nan()));
+    }
+
+    #[test]
+    fn errors() {
+        let options = CsvOptions::default();
+
+        assert_eq!(
+            parse_error("1,2\n\n3,x\n", &options),
+            (
+                3,
+                2,
+                "cannot parse \"x\": invalid float literal".to_string()
+            )
+        );
+        assert_eq!(
+            parse_error("1,2\n3\n", &options),
+            (2, 2, "expected 2 fields, found 1".to_string())
+        );
+        assert_eq!(
+            parse_error("1,2\n3,4,5\n", &options),
+            (2, 3, "expected 2 fields, found 3".to_string())
+        );
+        assert_eq!(
+            parse_error("1,\"2\n", &options),
+            (1, 2, "unterminated quoted field".to_string())
+        );
+        assert!(parse_error("1;2\n", &options)
+            .2
+            .starts_with("cannot parse \"1;2\""));
+    }
+
+    #[test]
+    fn quoting() {
+        let a = Matrix::from([
+            ["a,b", "say \"hi\"", " a "],
+            ["two\nlines", "plain", "\tend"],
+        ]);
+        let mut csv = Vec::new();
+        a.write_csv(&mut csv, &CsvOptions::default()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv.clone()).unwrap(),
+            "\"a,b\",\"say \"\"hi\"\"\",\" a \"\n\"two\nlines\",plain,\"\tend\"\n"
+        );
+        let b: Matrix<String> = Matrix::read_csv(&csv[..], &CsvOptions::default()).unwrap();
+        assert_eq!(b, a.map(|s| s.to_string()));
+
+        let csv = " \" x \" , y ,\"\"\n";
+        let c: Matrix<String> = Matrix::read_csv(
+            csv.as_bytes(),
+            &CsvOptions {
+                missing: Missing::Fill("-".to_string()),
+                ..CsvOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(c, Matrix::from([[" x ", "y", "-"]]).map(|s| s.to_string()));
+    }
+
+    #[test]
+    fn round_trip() {
+        let a: Matrix<f64> = Matrix::random_normal(7, 5, 0., 1., 0);
+        let mut csv = Vec::new();
+        a.to_layout(Layout::ColumnMajor)
+            .write_csv(&mut csv, &CsvOptions::tsv())
+            .unwrap();
+
+        assert_eq!(Matrix::read_csv(&csv[..], &CsvOptions::tsv()).unwrap(), a);
+    }
+
+    #[test]
+    fn rows() {
+        let csv = "1,2\n3,4\n5,x\n";
+        let mut rows = Vec::new();
+        let result = Matrix::<i64>::read_csv_rows(csv.as_bytes(), &CsvOptions::default(), |row| {
+            rows.push(row.to_vec())
+        });
+
+        assert!(matches!(
+            result,
+            Err(MatrixError::Parse {
+                line: 3,
+                column: 2,
+                ..
+            })
+        ));
+        assert_eq!(rows, [[1, 2], [3, 4]]);
+    }
+}
@@ -0,0 +1,321 @@
+use super::{Matrix, Storage};
+use std::fmt;
+
+/// Options that control how a [`Matrix`] is printed by
+/// [`Matrix::display_with`], in the spirit of NumPy's printoptions.
+///
+/// A matrix with more than `threshold` elements is summarized: only the
+/// first and last `edge_items` rows and columns are printed, with `…` in
+/// place of the others. Independently, columns are left out from the middle
+/// until each line fits in `line_width` characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PrintOptions {
+    /// Number of rows and columns printed at each edge of a summarized
+    /// matrix.
+    pub edge_items: usize,
+    /// Number of elements above which a matrix is summarized.
+    pub threshold: usize,
+    /// Maximum number of characters per line, unless a single column is
+    /// wider.
+    pub line_width: usize,
+}
+
+impl Default for PrintOptions {
+    /// Returns NumPy's defaults: 3 edge items, a threshold of 1000 elements
+    /// and lines of 75 characters.
+    fn default() -> Self {
+        Self {
+            edge_items: 3,
+            threshold: 1000,
+            line_width: 75,
+        }
+    }
+}
+
+/// A [`Matrix`] with its [`PrintOptions`], returned by
+/// [`Matrix::display_with`].
+pub struct MatrixDisplay<'a, T, S = Vec<T>> {
+    matrix: &'a Matrix<T, S>,
+    options: PrintOptions,
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: Storage<T>,
+{
+    /// Returns an object that prints the matrix with `options` through
+    /// [`Display`] or [`LowerExp`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matrix::{Matrix, PrintOptions};
+    ///
+    /// let a: Matrix<i32> = Matrix::ones(100, 100);
+    /// let options = PrintOptions { edge_items: 1, ..PrintOptions::default() };
+    ///
+    /// assert_eq!(a.display_with(options).to_string(), "[[1 … 1]\n …\n [1 … 1]]");
+    /// ```
+    ///
+    /// [`Display`]: fmt::Display
+    /// [`LowerExp`]: fmt::LowerExp
+    pub fn display_with(&self, options: PrintOptions) -> MatrixDisplay<'_, T, S> {
+        MatrixDisplay {
+            matrix: self,
+            options,
+        }
+    }
+}
+
+/// Prints the matrix row by row, with its columns aligned, as in NumPy.
+///
+/// The precision of the formatter, as in `{:.3}`, applies to each element.
+/// Large matrices are summarized with the default [`PrintOptions`]; see
+/// [`Matrix::display_with`] to change them. An empty matrix prints as
+/// `[]`.
+impl<T, S> fmt::Display for Matrix<T, S>
+where
+    T: fmt::Display,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display_with(PrintOptions::default()), f)
+    }
+}
+
+/// Prints the matrix as its [`Display`] implementation does, but with
+/// each element in scientific notation.
+///
+/// [`Display`]: fmt::Display
+impl<T, S> fmt::LowerExp for Matrix<T, S>
+where
+    T: fmt::LowerExp,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerExp::fmt(&self.display_with(PrintOptions::default()), f)
+    }
+}
+
+impl<T, S> fmt::Display for MatrixDisplay<'_, T, S>
+where
+    T: fmt::Display,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision();
+        render(self.matrix, &self.options, f, |x| match precision {
+            Some(precision) => format!("{x:.precision$}"),
+            None => format!("{x}"),
+        })
+    }
+}
+
+impl<T, S> fmt::LowerExp for MatrixDisplay<'_, T, S>
+where
+    T: fmt::LowerExp,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision();
+        render(self.matrix, &self.options, f, |x| match precision {
+            Some(precision) => format!("{x:.precision$e}"),
+            None => format!("{x:e}"),
+        })
+    }
+}
+
+/// Writes `matrix` to `f`, formatting each printed element with `cell`.
+fn render<T, S, F>(
+    matrix: &Matrix<T, S>,
+    options: &PrintOptions,
+    f: &mut fmt::Formatter<'_>,
+    cell: F,
+) -> fmt::Result
+where
+    S: Storage<T>,
+    F: Fn(&T) -> String,
+{
+    let (num_rows, num_columns) = matrix.shape();
+    if num_rows == 0 || num_columns == 0 {
+        return f.write_str("[]");
+    }
+
+    let summarize = num_rows * num_columns > options.threshold;
+    let rows = edges(num_rows, options.edge_items, summarize);
+    let columns = edges(num_columns, options.edge_items, summarize);
+
+    // The cells of the printed rows, in the candidate columns.
+    let cells: Vec<Option<Vec<String>>> = rows
+        .iter()
+        .map(|i| {
+            i.map(|i| {
+                columns
+                    .iter()
+                    .flatten()
+                    .map(|&j| cell(&matrix[(i, j)]))
+                    .collect()
+            })
+        })
+        .collect();
+    let widths: Vec<usize> = (0..columns.iter().flatten().count())
+        .map(|k| {
+            cells
+                .iter()
+                .flatten()
+                .map(|row| row[k].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let columns = fit(&columns, &widths, options.line_width);
+
+    for (r, row) in cells.iter().enumerate() {
+        f.write_str(if r == 0 { "[" } else { " " })?;
+        match row {
+            None => f.write_str("…")?,
+            Some(row) => {
+                f.write_str("[")?;
+                for (c, column) in columns.iter().enumerate() {
+                    if c > 0 {
+                        f.write_str(" ")?;
+                    }
+                    match column {
+                        None => f.write_str("…")?,
+                        Some(k) => write!(f, "{:>width$}", row[*k], width = widths[*k])?,
+                    }
+                }
+                f.write_str("]")?;
+            }
+        }
+        f.write_str(if r + 1 == cells.len() { "]" } else { "\n" })?;
+    }
+    Ok(())
+}
+
+/// Returns the indices printed along an axis of length `len`, with `None`
+/// in place of the ones left out.
+fn edges(len: usize, edge_items: usize, summarize: bool) -> Vec<Option<usize>> {
+    if summarize && len > 2 * edge_items {
+        let head = (0..edge_items).map(Some);
+        let tail = (len - edge_items..len).map(Some);
+        head.chain([None]).chain(tail).collect()
+    } else {
+        (0..len).map(Some).collect()
+    }
+}
+
+/// Chooses, among the candidate `columns`, of the given `widths`, the ones
+/// that fit in `line_width` characters, taken alternately from the left
+/// and right edges. Returns the positions of the chosen columns among the
+/// candidates, with `None` in place of the ones left out.
+fn fit(columns: &[Option<usize>], widths: &[usize], line_width: usize) -> Vec<Option<usize>> {
+    // Two brackets on each side, a space between adjacent columns, and the
+    // ellipsis of a summarized matrix.
+    let ellipsis = columns.len() - widths.len();
+    let total = 4 + widths.iter().sum::<usize>() + ellipsis + columns.len() - 1;
+    if total <= line_width {
+        let mut k = 0;
+        return columns
+            .iter()
+            .map(|column| {
+                column.map(|_| {
+                    k += 1;
+                    k - 1
+                })
+            })
+            .collect();
+    }
+
+    // Start from the ellipsis, its separator and the brackets, and add the
+    // first column even if it does not fit.
+    let n = widths.len();
+    let (mut left, mut right) = (0, 0);
+    let mut used = 4 + 2;
+    while left + right < n {
+        let (k, is_left) = if left <= right {
+            (left, true)
+        } else {
+            (n - 1 - right, false)
+        };
+        let needed = widths[k] + 1;
+        if used + needed > line_width && left + right > 0 {
+            break;
+        }
+        used += needed;
+        if is_left {
+            left += 1;
+        } else {
+            right += 1;
+        }
+    }
+    (0..left)
+        .map(Some)
+        .chain([None])
+        .chain((n - right..n).map(Some))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_display {
+    use crate::{Layout, Matrix, PrintOptions};
+
+    #[test]
+    fn aligned() {
+        let a = Matrix::from([[1, -20, 3], [400, 5, 60]]);
+
+        assert_eq!(a.to_string(), "[[  1 -20  3]\n [400   5 60]]");
+        assert_eq!(a.to_layout(Layout::ColumnMajor).to_string(), a.to_string());
+        assert_eq!(Matrix::from([["a", "bc"]]).to_string(), "[[a bc]]");
+    }
+
+    #[test]
+    fn precision_and_exponent() {
+        let a: Matrix<f64> = Matrix::from([[1., 0.5], [-2.25, 1e6]]);
+
+        assert_eq!(
+            format!("{a:.2}"),
+            "[[ 1.00       0.50]\n [-2.25 1000000.00]]"
+        );
+        assert_eq!(format!("{a:e}"), "[[    1e0 5e-1]\n [-2.25e0  1e6]]");
+        assert_eq!(format!("{:.1e}", Matrix::from([[1234.5f64]])), "[[1.2e3]]");
+    }
+
+    #[test]
+    fn summarized() {
+        let mut a: Matrix<usize> = Matrix::zeros(40, 40);
+        a.apply_indexed(|i, j, x| *x = i * 100 + j);
+
+        let printed = a.to_string();
+        assert!(printed.starts_with("[[   0    1    2 …   37   38   39]\n"));
+        assert!(printed.contains("\n …\n"));
+        assert!(printed.ends_with("[3900 3901 3902 … 3937 3938 3939]]"));
+        assert_eq!(printed.lines().count(), 7);
+
+        let b: Matrix<usize> = Matrix::zeros(40, 25);
+        assert_eq!(b.to_string().lines().count(), 40);
+        assert!(!b.to_string().contains('…'));
+    }
+
+    #[test]
+    fn line_width() {
+        let a: Matrix<u32> = Matrix::full(2, 10, 12345);
+        let options = PrintOptions {
+            line_width: 30,
+            ..PrintOptions::default()
+        };
+        let printed = a.display_with(options).to_string();
+
+        assert_eq!(
+            printed,
+            "[[12345 12345 … 12345 12345]\n [12345 12345 … 12345 12345]]"
+        );
+        assert!(printed.lines().all(|line| line.chars().count() <= 30));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(Matrix::<f32>::zeros(0, 3).to_string(), "[]");
+        assert_eq!(Matrix::<f32>::zeros(3, 0).to_string(), "[]");
+    }
+}
@@ -0,0 +1,420 @@
+use super::{Layout, Matrix, Storage, StorageMut};
+use num_traits::Float;
+use rayon::prelude::*;
+use std::ops::{Div, Mul};
+
+impl<T, S> Matrix<T, S>
+where
+    S: Storage<T>,
+{
+    /// Returns a matrix of the same shape and layout whose elements are the
+    /// results of applying `f` to the elements of this one.
+    pub fn map<U, F>(&self, f: F) -> Matrix<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        let data = self.lanes().flatten().map(f).collect();
+        Matrix::from_layout(self.num_rows, self.num_columns, data, self.layout)
+    }
+
+    /// Like [`map`], but applies `f` to the lanes of the matrix in parallel.
+    ///
+    /// [`map`]: Matrix::map
+    pub fn par_map<U, F>(&self, f: F) -> Matrix<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&T) -> U + Sync,
+    {
+        let lanes: Vec<&[T]> = self.lanes().collect();
+        let data = lanes
+            .into_par_iter()
+            .flat_map_iter(|lane| lane.iter().map(&f))
+            .collect();
+        Matrix::from_layout(self.num_rows, self.num_columns, data, self.layout)
+    }
+
+    /// Returns a matrix with the layout of this one whose element `(i, j)`
+    /// is `f(&self[(i, j)], &other[(i, j)])`.
+    ///
+    /// `other` may have any storage and layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices do not have the same shape.
+    #[track_caller]
+    pub fn zip_with<U, V, SU, F>(&self, other: &Matrix<U, SU>, mut f: F) -> Matrix<V>
+    where
+        SU: Storage<U>,
+        F: FnMut(&T, &U) -> V,
+    {
+        assert_same_shape(self.shape(), other.shape());
+        let mut data = Vec::with_capacity(self.num_rows * self.num_columns);
+        if self.layout == other.layout {
+            for (a, b) in self.lanes().zip(other.lanes()) {
+                data.extend(a.iter().zip(b).map(|(x, y)| f(x, y)));
+            }
+        } else {
+            let layout = self.layout;
+            for (k, a) in self.lanes().enumerate() {
+                data.extend(a.iter().enumerate().map(|(l, x)| {
+                    let (i, j) = lane_index(layout, k, l);
+                    f(x, &other[(i, j)])
+                }));
+            }
+        }
+        Matrix::from_layout(self.num_rows, self.num_columns, data, self.layout)
+    }
+
+    /// Like [`zip_with`], but applies `f` to the lanes of the matrices in
+    /// parallel.
+    ///
+    /// [`zip_with`]: Matrix::zip_with
+    #[track_caller]
+    pub fn par_zip_with<U, V, SU, F>(&self, other: &Matrix<U, SU>, f: F) -> Matrix<V>
+    where
+        T: Sync,
+        U: Sync,
+        V: Send,
+        SU: Storage<U>,
+        F: Fn(&T, &U) -> V + Sync,
+    {
+        assert_same_shape(self.shape(), other.shape());
+        // A borrowed view is `Sync` whatever the storage of `other`.
+        let (layout, other) = (self.layout, &other.view());
+        let f = &f;
+        let lanes: Vec<&[T]> = self.lanes().collect();
+        let data = lanes
+            .into_par_iter()
+            .enumerate()
+            .flat_map_iter(|(k, a)| {
+                a.iter().enumerate().map(move |(l, x)| {
+                    let (i, j) = lane_index(layout, k, l);
+                    f(x, &other[(i, j)])
+                })
+            })
+            .collect();
+        Matrix::from_layout(self.num_rows, self.num_columns, data, self.layout)
+    }
+
+    /// Folds every element into an accumulator by applying `f`, in the
+    /// order in which the elements are stored.
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &T) -> B,
+    {
+        self.lanes().flatten().fold(init, f)
+    }
+
+    /// Folds each lane of the matrix in parallel, starting from `identity`,
+    /// and then combines the results of the lanes, in order, with `reduce`.
+    ///
+    /// For a given matrix, the result does not depend on the number of
+    /// threads.
+    pub fn par_fold<B, F, R>(&self, identity: B, fold: F, reduce: R) -> B
+    where
+        T: Sync,
+        B: Clone + Send + Sync,
+        F: Fn(B, &T) -> B + Sync,
+        R: FnMut(B, B) -> B,
+    {
+        let lanes: Vec<&[T]> = self.lanes().collect();
+        let partials: Vec<B> = lanes
+            .into_par_iter()
+            .map(|lane| lane.iter().fold(identity.clone(), &fold))
+            .collect();
+        partials.into_iter().fold(identity, reduce)
+    }
+
+    /// Calls `f` with the row index, the column index and a reference to
+    /// each element, in the order in which the elements are stored.
+    pub fn for_each_indexed<F>(&self, mut f: F)
+    where
+        F: FnMut(usize, usize, &T),
+    {
+        let layout = self.layout;
+        for (k, lane) in self.lanes().enumerate() {
+            for (l, x) in lane.iter().enumerate() {
+                let (i, j) = lane_index(layout, k, l);
+                f(i, j, x);
+            }
+        }
+    }
+
+    /// Like [`for_each_indexed`], but visits the lanes of the matrix in
+    /// parallel, in no particular order.
+    ///
+    /// [`for_each_indexed`]: Matrix::for_each_indexed
+    pub fn par_for_each_indexed<F>(&self, f: F)
+    where
+        T: Sync,
+        F: Fn(usize, usize, &T) + Sync,
+    {
+        let layout = self.layout;
+        let lanes: Vec<&[T]> = self.lanes().collect();
+        lanes.into_par_iter().enumerate().for_each(|(k, lane)| {
+            for (l, x) in lane.iter().enumerate() {
+                let (i, j) = lane_index(layout, k, l);
+                f(i, j, x);
+            }
+        });
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: StorageMut<T>,
+{
+    /// Applies `f` to each element of the matrix, in place.
+    pub fn map_inplace<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T),
+    {
+        self.lanes_mut().flatten().for_each(f);
+    }
+
+    /// Like [`map_inplace`], but visits the lanes of the matrix in parallel.
+    ///
+    /// [`map_inplace`]: Matrix::map_inplace
+    pub fn par_map_inplace<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) + Sync,
+    {
+        let lanes: Vec<&mut [T]> = self.lanes_mut().collect();
+        lanes
+            .into_par_iter()
+            .for_each(|lane| lane.iter_mut().for_each(&f));
+    }
+
+    /// Calls `f` with the row index, the column index and a mutable
+    /// reference to each element, in the order in which the elements are
+    /// stored.
+    pub fn apply_indexed<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, usize, &mut T),
+    {
+        let layout = self.layout;
+        for (k, lane) in self.lanes_mut().enumerate() {
+            for (l, x) in lane.iter_mut().enumerate() {
+                let (i, j) = lane_index(layout, k, l);
+                f(i, j, x);
+            }
+        }
+    }
+
+    /// Like [`apply_indexed`], but visits the lanes of the matrix in
+    /// parallel, in no particular order.
+    ///
+    /// [`apply_indexed`]: Matrix::apply_indexed
+    pub fn par_apply_indexed<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(usize, usize, &mut T) + Sync,
+    {
+        let layout = self.layout;
+        let lanes: Vec<&mut [T]> = self.lanes_mut().collect();
+        lanes.into_par_iter().enumerate().for_each(|(k, lane)| {
+            for (l, x) in lane.iter_mut().enumerate() {
+                let (i, j) = lane_index(layout, k, l);
+                f(i, j, x);
+            }
+        });
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    T: Float,
+    S: Storage<T>,
+{
+    /// Returns the element-wise, or Hadamard, product of the matrices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices do not have the same shape.
+    #[track_caller]
+    pub fn hadamard<SU>(&self, other: &Matrix<T, SU>) -> Matrix<T>
+    where
+        SU: Storage<T>,
+    {
+        self.zip_with(other, |&x, &y| Mul::mul(x, y))
+    }
+
+    /// Returns the element-wise quotient of the matrices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices do not have the same shape.
+    #[track_caller]
+    pub fn hadamard_div<SU>(&self, other: &Matrix<T, SU>) -> Matrix<T>
+    where
+        SU: Storage<T>,
+    {
+        self.zip_with(other, |&x, &y| Div::div(x, y))
+    }
+
+    /// Returns the element-wise absolute value of the matrix.
+    pub fn abs(&self) -> Matrix<T> {
+        self.map(|x| x.abs())
+    }
+
+    /// Returns the element-wise square root of the matrix.
+    pub fn sqrt(&self) -> Matrix<T> {
+        self.map(|x| x.sqrt())
+    }
+
+    /// Returns the element-wise exponential of the matrix.
+    pub fn exp(&self) -> Matrix<T> {
+        self.map(|x| x.exp())
+    }
+
+    /// Raises each element of the matrix to the integer power `n`.
+    pub fn powi(&self, n: i32) -> Matrix<T> {
+        self.map(|x| x.powi(n))
+    }
+
+    /// Restricts each element of the matrix to the interval `[min, max]`.
+    ///
+    /// NaN elements are left as they are.
+    pub fn clamp(&self, min: T, max: T) -> Matrix<T> {
+        self.map(|&x| {
+            if x < min {
+                min
+            } else if x > max {
+                max
+            } else {
+                x
+            }
+        })
+    }
+}
+
+/// Returns the row and column of the element at position `l` of lane `k`
+/// of a matrix with the given layout.
+#[inline]
+const fn lane_index(layout: Layout, k: usize, l: usize) -> (usize, usize) {
+    match layout {
+        Layout::RowMajor => (k, l),
+        Layout::ColumnMajor => (l, k),
+    }
+}
+
+#[track_caller]
+fn assert_same_shape(shape: (usize, usize), other_shape: (usize, usize)) {
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_failed(shape: (usize, usize), other_shape: (usize, usize)) -> ! {
+        panic!(
+            "`self.shape()` (is {shape:?}) should be equal to `other.shape()` (is {other_shape:?})"
+        );
+    }
+
+    if shape != other_shape {
+        assert_failed(shape, other_shape);
+    }
+}
+
+#[cfg(test)]
+mod test_elementwise {
+    use crate::{Layout, Matrix};
+    use std::sync::Mutex;
+
+    #[test]
+    fn map() {
+        let a = Matrix::from_column_major(2, 3, vec![0, 1, 2, 3, 4, 5]);
+        let b = a.map(|x| x.to_string());
+
+        assert_eq!(b.layout(), Layout::ColumnMajor);
+        assert_eq!(b[(1, 2)], "5");
+        assert_eq!(a.par_map(|x| x * 2), a.map(|x| x * 2));
+
+        let c = Matrix::with_padding(2, 2, 3, 1);
+        assert_eq!(c.map(|x| x + 1), Matrix::from([[2, 2], [2, 2]]));
+    }
+
+    #[test]
+    fn map_inplace() {
+        let mut a = Matrix::from([[1, 2], [3, 4]]);
+
+        a.map_inplace(|x| *x *= 10);
+        assert_eq!(a, Matrix::from([[10, 20], [30, 40]]));
+
+        a.par_map_inplace(|x| *x += 1);
+        assert_eq!(a, Matrix::from([[11, 21], [31, 41]]));
+    }
+
+    #[test]
+    fn zip_with() {
+        let a = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+        let b = a.to_layout(Layout::ColumnMajor);
+
+        assert_eq!(a.zip_with(&b, |x, y| x - y), Matrix::zeros(2, 3));
+        assert_eq!(b.zip_with(&a, |x, y| x + y), a.map(|x| 2 * x));
+        assert_eq!(
+            a.par_zip_with(&b, |x, y| x * y),
+            a.zip_with(&a, |x, y| x * y)
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`self.shape()` (is (2, 3)) should be equal to `other.shape()` (is (3, 2))"
+    )]
+    fn zip_with_different_shapes() {
+        let a: Matrix<i32> = Matrix::zeros(2, 3);
+        a.zip_with(&Matrix::<i32>::zeros(3, 2), |x, y| x + y);
+    }
+
+    #[test]
+    fn fold() {
+        let a = Matrix::from([[1, 2], [3, 4]]).into_layout(Layout::ColumnMajor);
+
+        assert_eq!(a.fold(0, |sum, x| sum + x), 10);
+        assert_eq!(
+            a.fold(vec![], |mut v, &x| {
+                v.push(x);
+                v
+            }),
+            [1, 3, 2, 4]
+        );
+        assert_eq!(a.par_fold(0, |sum, x| sum + x, |a, b| a + b), 10);
+        assert_eq!(
+            Matrix::<i32>::zeros(0, 3).par_fold(0, |s, x| s + x, |a, b| a + b),
+            0
+        );
+    }
+
+    #[test]
+    fn indexed() {
+        let mut a: Matrix<usize> = Matrix::zeros(2, 3);
+
+        a.apply_indexed(|i, j, x| *x = 10 * i + j);
+        assert_eq!(a, Matrix::from([[0, 1, 2], [10, 11, 12]]));
+
+        let mut b = a.to_layout(Layout::ColumnMajor);
+        b.par_apply_indexed(|i, j, x| *x -= 10 * i + j);
+        assert_eq!(b, Matrix::zeros(2, 3));
+
+        let visited = Mutex::new(vec![]);
+        a.par_for_each_indexed(|i, j, &x| visited.lock().unwrap().push((i, j, x)));
+        let mut visited = visited.into_inner().unwrap();
+        visited.sort();
+
+        let mut expected = vec![];
+        a.for_each_indexed(|i, j, &x| expected.push((i, j, x)));
+        assert_eq!(visited, expected);
+    }
+
+    #[test]
+    fn float_operations() {
+        let a = Matrix::from([[-4., 1.], [9., -16.]]);
+
+        assert_eq!(a.abs().sqrt(), Matrix::from([[2., 1.], [3., 4.]]));
+        assert_eq!(a.powi(2), a.hadamard(&a));
+        assert_eq!(a.hadamard_div(&a), Matrix::ones(2, 2));
+        assert_eq!(a.clamp(-1., 1.), Matrix::from([[-1., 1.], [1., -1.]]));
+        assert_eq!(Matrix::<f64>::zeros(1, 2).exp(), Matrix::ones(1, 2));
+    }
+}
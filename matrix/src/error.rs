@@ -0,0 +1,66 @@
+use std::error::Error;
+use std::{fmt, io};
+
+/// The error type for fallible operations on a [`Matrix`].
+///
+/// [`Matrix`]: super::Matrix
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MatrixError {
+    /// The elements of the matrix are not contiguous in memory: each of
+    /// its lanes, of `lane_len` elements, starts `leading_dimension`
+    /// elements after the previous one.
+    Padded {
+        leading_dimension: usize,
+        lane_len: usize,
+    },
+    /// A field of a text input could not be parsed, or a record of it has
+    /// the wrong number of fields. Lines and columns count from 1; columns
+    /// count fields, not characters.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// A binary input is malformed, or holds elements of another type.
+    Format { message: String },
+    /// An I/O operation failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Padded {
+                leading_dimension,
+                lane_len,
+            } => write!(
+                f,
+                "the matrix is padded: its leading dimension (is {leading_dimension}) \
+                is larger than its lanes (are {lane_len} elements long)"
+            ),
+            Self::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "parse error at line {line}, column {column}: {message}"),
+            Self::Format { message } => write!(f, "format error: {message}"),
+            Self::Io(error) => write!(f, "I/O error: {error}"),
+        }
+    }
+}
+
+impl Error for MatrixError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MatrixError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
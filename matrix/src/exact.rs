@@ -0,0 +1,551 @@
+use super::Matrix;
+use num_traits::{One, Signed, Zero};
+use std::ops::{Div, Mul, Neg, Sub};
+
+/// A scalar type whose arithmetic is exact, such as [`ModP<P>`].
+///
+/// Gaussian elimination over a `Field` never loses precision, so the
+/// methods below only need to find a nonzero pivot, not the largest one.
+///
+/// [`ModP<P>`]: super::ModP
+pub trait Field:
+    Clone
+    + PartialEq
+    + Zero
+    + One
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A signed integer type, for the fraction-free methods such as
+/// [`Matrix::bareiss_determinant`]: a primitive signed integer or, with the
+/// `rational` feature, `BigInt`.
+///
+/// Floating point types are not integers, and their remainders would make
+/// the fraction-free elimination meaningless:
+///
+/// ```compile_fail
+/// use matrix::Matrix;
+///
+/// Matrix::from([[1., 2.], [3., 4.]]).bareiss_determinant();
+/// ```
+pub trait SignedInteger: Clone + Signed + private::Sealed {}
+
+macro_rules! impl_signed_integer {
+    ($($t:ty)*) => {$(
+        impl private::Sealed for $t {}
+        impl SignedInteger for $t {}
+    )*};
+}
+
+impl_signed_integer!(i8 i16 i32 i64 i128 isize);
+#[cfg(feature = "rational")]
+impl_signed_integer!(num_bigint::BigInt);
+
+impl<T: Field> Matrix<T> {
+    /// Returns the rank of the matrix, that is, the number of linearly
+    /// independent rows.
+    pub fn rank(&self) -> usize {
+        let mut data = self.to_row_major_vec();
+        reduce_rows(&mut data, self.num_rows, self.num_columns).len()
+    }
+
+    /// Returns the reduced row echelon form of the matrix.
+    ///
+    /// In this form, the first nonzero element of each row, its pivot, is
+    /// one and is the only nonzero element in its column, and each pivot is
+    /// to the right of the pivots of the rows above it.
+    pub fn rref(&self) -> Self {
+        let mut data = self.to_row_major_vec();
+        reduce_rows(&mut data, self.num_rows, self.num_columns);
+        Self::from_row_major(self.num_rows, self.num_columns, data)
+    }
+
+    /// Returns the determinant of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn determinant(&self) -> T {
+        assert_square(self.num_rows, self.num_columns);
+
+        let n = self.num_rows;
+        let mut data = self.to_row_major_vec();
+        let mut determinant = T::one();
+
+        for c in 0..n {
+            let Some(p) = (c..n).find(|&p| !data[p * n + c].is_zero()) else {
+                return T::zero();
+            };
+            if p != c {
+                swap_rows(&mut data, n, p, c);
+                determinant = -determinant;
+            }
+
+            let pivot = data[c * n + c].clone();
+            for i in (c + 1)..n {
+                let factor = data[i * n + c].clone() / pivot.clone();
+                if !factor.is_zero() {
+                    eliminate(&mut data, n, i, c, &factor, c);
+                }
+            }
+            determinant = determinant * pivot;
+        }
+        determinant
+    }
+
+    /// Returns the inverse of the matrix, or `None` if it is singular.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn inverse(&self) -> Option<Self> {
+        assert_square(self.num_rows, self.num_columns);
+
+        // Reduces the augmented matrix `[A | I]` to `[I | A^-1]`.
+        let n = self.num_rows;
+        let mut data = Vec::with_capacity(2 * n * n);
+        for (i, row) in self.as_row_major().rows().enumerate() {
+            data.extend_from_slice(row);
+            data.extend((0..n).map(|j| if i == j { T::one() } else { T::zero() }));
+        }
+        if reduce_rows(&mut data, n, 2 * n)
+            .into_iter()
+            .take(n)
+            .ne(0..n)
+        {
+            return None;
+        }
+
+        let inverse = (0..n)
+            .flat_map(|i| data[(2 * i + 1) * n..(2 * i + 2) * n].iter().cloned())
+            .collect();
+        Some(Self::from_row_major(n, n, inverse))
+    }
+
+    /// Returns a basis of the null space of the matrix, that is, of the
+    /// solutions of `A x = 0`.
+    ///
+    /// Each basis vector has `num_columns` elements. The basis is empty if
+    /// the columns of the matrix are linearly independent.
+    pub fn null_space(&self) -> Vec<Vec<T>> {
+        let num_columns = self.num_columns;
+        let mut data = self.to_row_major_vec();
+        let pivots = reduce_rows(&mut data, self.num_rows, num_columns);
+
+        let mut is_pivot = vec![false; num_columns];
+        pivots.iter().for_each(|&c| is_pivot[c] = true);
+
+        (0..num_columns)
+            .filter(|&free| !is_pivot[free])
+            .map(|free| {
+                let mut vector = vec![T::zero(); num_columns];
+                vector[free] = T::one();
+                for (r, &c) in pivots.iter().enumerate() {
+                    vector[c] = -data[r * num_columns + free].clone();
+                }
+                vector
+            })
+            .collect()
+    }
+
+    /// Solves `A x = b` for `x`, returning `None` if the system is
+    /// inconsistent.
+    ///
+    /// If the system has many solutions, the one whose free variables are
+    /// all zero is returned; add any combination of [`null_space`] vectors
+    /// to obtain the others.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b.len() != self.num_rows()`.
+    ///
+    /// [`null_space`]: Matrix::null_space
+    pub fn solve(&self, b: &[T]) -> Option<Vec<T>> {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(b_len: usize, num_rows: usize) -> ! {
+            panic!("`b.len()` (is {b_len}) should be equal to `num_rows` (is {num_rows})");
+        }
+
+        if b.len() != self.num_rows {
+            assert_failed(b.len(), self.num_rows);
+        }
+
+        // Reduces the augmented matrix `[A | b]`.
+        let num_columns = self.num_columns;
+        let width = num_columns + 1;
+        let mut data = Vec::with_capacity(self.num_rows * width);
+        for (row, bi) in self.as_row_major().rows().zip(b.iter()) {
+            data.extend_from_slice(row);
+            data.push(bi.clone());
+        }
+        let pivots = reduce_rows(&mut data, self.num_rows, width);
+        if pivots.last() == Some(&num_columns) {
+            return None;
+        }
+
+        let mut x = vec![T::zero(); num_columns];
+        for (r, &c) in pivots.iter().enumerate() {
+            x[c] = data[r * width + num_columns].clone();
+        }
+        Some(x)
+    }
+}
+
+impl<T: SignedInteger> Matrix<T> {
+    /// Returns the determinant of the matrix, computed with Bareiss'
+    /// fraction-free elimination.
+    ///
+    /// Every intermediate value is a minor of the matrix and every division
+    /// is exact, so for integer types the result is exact as long as no
+    /// minor overflows `T`. Use a big integer type to rule overflow out.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn bareiss_determinant(&self) -> T {
+        assert_square(self.num_rows, self.num_columns);
+
+        let n = self.num_rows;
+        let mut data = self.to_row_major_vec();
+        let mut sign = T::one();
+        let mut previous_pivot = T::one();
+
+        for k in 0..n {
+            let Some(p) = (k..n).find(|&p| !data[p * n + k].is_zero()) else {
+                return T::zero();
+            };
+            if p != k {
+                swap_rows(&mut data, n, p, k);
+                sign = -sign;
+            }
+
+            let pivot = data[k * n + k].clone();
+            for i in (k + 1)..n {
+                let factor = data[i * n + k].clone();
+                for j in (k + 1)..n {
+                    let kj = data[k * n + j].clone();
+                    let ij = &mut data[i * n + j];
+                    *ij =
+                        (pivot.clone() * ij.clone() - factor.clone() * kj) / previous_pivot.clone();
+                }
+            }
+            previous_pivot = pivot;
+        }
+        sign * previous_pivot
+    }
+
+    /// Returns the rank of the matrix, computed with fraction-free
+    /// elimination.
+    pub fn integer_rank(&self) -> usize {
+        let mut data = self.to_row_major_vec();
+        reduce_rows_fraction_free(&mut data, self.num_rows, self.num_columns)
+            .0
+            .len()
+    }
+
+    /// Returns a basis of the null space of the matrix, that is, of the
+    /// solutions of `A x = 0`, computed with fraction-free elimination.
+    ///
+    /// Each basis vector has `num_columns` elements, no common divisor
+    /// other than one, and a positive element at its free variable.
+    pub fn integer_null_space(&self) -> Vec<Vec<T>> {
+        let num_columns = self.num_columns;
+        let mut data = self.to_row_major_vec();
+        let (pivots, denominator) =
+            reduce_rows_fraction_free(&mut data, self.num_rows, num_columns);
+
+        let mut is_pivot = vec![false; num_columns];
+        pivots.iter().for_each(|&c| is_pivot[c] = true);
+
+        (0..num_columns)
+            .filter(|&free| !is_pivot[free])
+            .map(|free| {
+                // `data` is `denominator` times the reduced row echelon form.
+                let mut vector = vec![T::zero(); num_columns];
+                vector[free] = denominator.clone();
+                for (r, &c) in pivots.iter().enumerate() {
+                    vector[c] = -data[r * num_columns + free].clone();
+                }
+
+                let divisor = vector.iter().cloned().fold(T::zero(), gcd);
+                let divisor = if vector[free].is_negative() {
+                    -divisor
+                } else {
+                    divisor
+                };
+                vector.into_iter().map(|x| x / divisor.clone()).collect()
+            })
+            .collect()
+    }
+}
+
+#[track_caller]
+pub(super) fn assert_square(num_rows: usize, num_columns: usize) {
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_failed(num_rows: usize, num_columns: usize) -> ! {
+        panic!("`num_rows` (is {num_rows}) should be equal to `num_columns` (is {num_columns})");
+    }
+
+    if num_rows != num_columns {
+        assert_failed(num_rows, num_columns);
+    }
+}
+
+/// Swaps rows `i` and `k` of the row-major array `data`.
+fn swap_rows<T>(data: &mut [T], num_columns: usize, i: usize, k: usize) {
+    let (i, k) = (i.min(k), i.max(k));
+    let (head, tail) = data.split_at_mut(k * num_columns);
+    head[i * num_columns..(i + 1) * num_columns].swap_with_slice(&mut tail[..num_columns]);
+}
+
+/// Subtracts `factor` times row `r` from row `i` of the row-major array
+/// `data`, skipping the columns before `start`.
+fn eliminate<T: Field>(
+    data: &mut [T],
+    num_columns: usize,
+    i: usize,
+    r: usize,
+    factor: &T,
+    start: usize,
+) {
+    for j in start..num_columns {
+        let rj = data[r * num_columns + j].clone();
+        let ij = &mut data[i * num_columns + j];
+        *ij = ij.clone() - factor.clone() * rj;
+    }
+}
+
+/// Reduces the row-major array `data` with shape `(num_rows, num_columns)`
+/// to reduced row echelon form in place, returning its pivot columns in
+/// increasing order.
+pub(super) fn reduce_rows<T: Field>(
+    data: &mut [T],
+    num_rows: usize,
+    num_columns: usize,
+) -> Vec<usize> {
+    let mut pivots = Vec::new();
+
+    for c in 0..num_columns {
+        let r = pivots.len();
+        if r == num_rows {
+            break;
+        }
+        let Some(p) = (r..num_rows).find(|&p| !data[p * num_columns + c].is_zero()) else {
+            continue;
+        };
+        if p != r {
+            swap_rows(data, num_columns, p, r);
+        }
+
+        let pivot = data[r * num_columns + c].clone();
+        for x in &mut data[r * num_columns + c..(r + 1) * num_columns] {
+            *x = x.clone() / pivot.clone();
+        }
+        for i in (0..num_rows).filter(|&i| i != r) {
+            let factor = data[i * num_columns + c].clone();
+            if !factor.is_zero() {
+                eliminate(data, num_columns, i, r, &factor, c);
+            }
+        }
+        pivots.push(c);
+    }
+    pivots
+}
+
+/// Reduces the row-major array `data` with shape `(num_rows, num_columns)`
+/// to a multiple of its reduced row echelon form in place, using only exact
+/// divisions. Returns the pivot columns in increasing order and the common
+/// value of the pivots.
+fn reduce_rows_fraction_free<T: SignedInteger>(
+    data: &mut [T],
+    num_rows: usize,
+    num_columns: usize,
+) -> (Vec<usize>, T) {
+    let mut pivots = Vec::new();
+    let mut previous_pivot = T::one();
+
+    for c in 0..num_columns {
+        let r = pivots.len();
+        if r == num_rows {
+            break;
+        }
+        let Some(p) = (r..num_rows).find(|&p| !data[p * num_columns + c].is_zero()) else {
+            continue;
+        };
+        if p != r {
+            swap_rows(data, num_columns, p, r);
+        }
+
+        let pivot = data[r * num_columns + c].clone();
+        for i in (0..num_rows).filter(|&i| i != r) {
+            let factor = data[i * num_columns + c].clone();
+            for j in 0..num_columns {
+                let rj = data[r * num_columns + j].clone();
+                let ij = &mut data[i * num_columns + j];
+                *ij = (pivot.clone() * ij.clone() - factor.clone() * rj) / previous_pivot.clone();
+            }
+        }
+        previous_pivot = pivot;
+        pivots.push(c);
+    }
+    (pivots, previous_pivot)
+}
+
+/// Returns the nonnegative greatest common divisor of `a` and `b`.
+fn gcd<T: SignedInteger>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while !b.is_zero() {
+        let r = a.clone() % b.clone();
+        a = b;
+        b = r;
+    }
+    a
+}
+
+#[cfg(test)]
+mod test_exact {
+    use crate::{Matrix, ModP};
+
+    type F7 = ModP<7>;
+
+    fn f7<const N: usize, const M: usize>(array: [[i64; N]; M]) -> Matrix<F7> {
+        Matrix::from(array.map(|row| row.map(F7::from_i64)))
+    }
+
+    #[test]
+    fn rank() {
+        assert_eq!(f7([[1, 2], [3, 4]]).rank(), 2);
+        assert_eq!(f7([[1, 2, 3], [2, 4, 6]]).rank(), 1);
+        // The rows are independent over the rationals but not over GF(7).
+        assert_eq!(f7([[1, 1], [1, 8]]).rank(), 1);
+    }
+
+    #[test]
+    fn rref() {
+        let a = f7([[0, 2, 4], [1, 1, 1], [2, 2, 2]]);
+
+        assert_eq!(a.rref(), f7([[1, 0, -1], [0, 1, 2], [0, 0, 0]]));
+    }
+
+    #[test]
+    fn determinant() {
+        assert_eq!(f7([[1, 2], [3, 4]]).determinant(), F7::from_i64(-2));
+        assert_eq!(
+            f7([[0, 1, 0], [1, 0, 0], [0, 0, 3]]).determinant(),
+            F7::from_i64(-3)
+        );
+        assert_eq!(f7([[1, 2], [2, 4]]).determinant(), F7::new(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "`num_rows` (is 1) should be equal to `num_columns` (is 2)")]
+    fn determinant_of_non_square_matrix() {
+        let _ = f7([[1, 2]]).determinant();
+    }
+
+    #[test]
+    fn inverse() {
+        let a = f7([[2, 1, 0], [0, 1, 3], [1, 0, 1]]);
+        let inverse = a.inverse().unwrap();
+
+        assert_eq!(
+            Matrix::mul(&a, &inverse),
+            f7([[1, 0, 0], [0, 1, 0], [0, 0, 1]])
+        );
+        assert_eq!(f7([[1, 2], [2, 4]]).inverse(), None);
+    }
+
+    #[test]
+    fn null_space() {
+        let a = f7([[1, 2, 3], [2, 4, 6]]);
+        let basis = a.null_space();
+
+        assert_eq!(basis.len(), 2);
+        for vector in basis {
+            let x = Matrix::from_row_major(3, 1, vector);
+            assert_eq!(Matrix::mul(&a, &x), f7([[0], [0]]));
+        }
+        assert!(f7([[1, 0], [0, 1]]).null_space().is_empty());
+    }
+
+    #[test]
+    fn solve() {
+        let a = f7([[1, 1, 0], [0, 1, 1]]);
+        let b = [F7::new(3), F7::new(5)];
+        let x = a.solve(&b).unwrap();
+        let x = Matrix::from_row_major(3, 1, x);
+
+        assert_eq!(Matrix::mul(&a, &x).as_flattened(), b);
+        assert_eq!(f7([[1, 1], [1, 1]]).solve(&[F7::new(0), F7::new(1)]), None);
+    }
+
+    #[test]
+    fn empty() {
+        let square: Matrix<F7> = Matrix::zeros(0, 0);
+        let wide: Matrix<F7> = Matrix::zeros(0, 2);
+
+        assert_eq!(square.rank(), 0);
+        assert_eq!(square.determinant(), F7::new(1));
+        assert_eq!(square.inverse(), Some(square.clone()));
+        assert_eq!(square.solve(&[]), Some(vec![]));
+        assert_eq!(wide.rref(), wide);
+        assert_eq!(
+            wide.null_space(),
+            vec![vec![F7::new(1), F7::new(0)], vec![F7::new(0), F7::new(1)]]
+        );
+        assert!(Matrix::<F7>::zeros(2, 0).null_space().is_empty());
+        assert_eq!(Matrix::<i64>::zeros(0, 0).bareiss_determinant(), 1);
+        assert_eq!(
+            Matrix::<i64>::zeros(0, 1).integer_null_space(),
+            vec![vec![1]]
+        );
+    }
+
+    #[test]
+    fn bareiss_determinant() {
+        let a: Matrix<i64> =
+            Matrix::from([[2, -1, 0, 3], [1, 3, -2, 0], [0, 4, 1, -1], [5, 0, 2, 2]]);
+
+        assert_eq!(a.bareiss_determinant(), -119);
+        assert_eq!(Matrix::from([[0, 1], [1, 0]]).bareiss_determinant(), -1);
+        assert_eq!(Matrix::from([[1, 2], [2, 4]]).bareiss_determinant(), 0);
+    }
+
+    #[test]
+    fn integer_rank() {
+        assert_eq!(
+            Matrix::from([[1, 2, 3], [4, 5, 6], [7, 8, 9]]).integer_rank(),
+            2
+        );
+        assert_eq!(Matrix::from([[1, 2], [3, 4]]).integer_rank(), 2);
+        assert_eq!(Matrix::from([[0, 0], [0, 0]]).integer_rank(), 0);
+    }
+
+    #[test]
+    fn integer_null_space() {
+        let a: Matrix<i64> = Matrix::from([[1, 2, 3], [4, 5, 6], [7, 8, 9]]);
+
+        assert_eq!(a.integer_null_space(), vec![vec![1, -2, 1]]);
+
+        let a: Matrix<i64> = Matrix::from([[2, 4, 6, 8], [3, 6, 1, 2]]);
+        let basis = a.integer_null_space();
+
+        assert_eq!(basis.len(), 2);
+        for vector in basis {
+            let x = Matrix::from_row_major(4, 1, vector);
+            assert_eq!(Matrix::mul(&a, &x), Matrix::from([[0], [0]]));
+        }
+    }
+}
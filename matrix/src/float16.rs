@@ -0,0 +1,188 @@
+use super::{Layout, Matrix};
+use half::slice::HalfFloatSliceExt;
+use half::{bf16, f16};
+use rayon::prelude::*;
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for half::f16 {}
+    impl Sealed for half::bf16 {}
+}
+
+/// A 16-bit floating point type, either [`f16`] or [`bf16`].
+///
+/// Half-precision matrices are meant for storage: their arithmetic is
+/// carried out in `f32`, which represents every value of both types
+/// exactly.
+pub trait HalfFloat: private::Sealed + Copy + Send + Sync {
+    /// Converts `src` into `f32` values, writing them to `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src.len() != dst.len()`.
+    fn convert_to_f32_slice(src: &[Self], dst: &mut [f32]);
+
+    /// Converts `src` into half-precision values, rounding them to the
+    /// nearest representable value and writing them to `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src.len() != dst.len()`.
+    fn convert_from_f32_slice(src: &[f32], dst: &mut [Self]);
+}
+
+impl HalfFloat for f16 {
+    #[inline]
+    fn convert_to_f32_slice(src: &[Self], dst: &mut [f32]) {
+        src.convert_to_f32_slice(dst);
+    }
+
+    #[inline]
+    fn convert_from_f32_slice(src: &[f32], dst: &mut [Self]) {
+        dst.convert_from_f32_slice(src);
+    }
+}
+
+impl HalfFloat for bf16 {
+    #[inline]
+    fn convert_to_f32_slice(src: &[Self], dst: &mut [f32]) {
+        src.convert_to_f32_slice(dst);
+    }
+
+    #[inline]
+    fn convert_from_f32_slice(src: &[f32], dst: &mut [Self]) {
+        dst.convert_from_f32_slice(src);
+    }
+}
+
+impl<H: HalfFloat> Matrix<H> {
+    /// Converts the matrix into a `Matrix<f32>`. The conversion is exact.
+    pub fn to_f32(&self) -> Matrix<f32> {
+        let mut data = vec![0f32; self.data.len()];
+        data.par_chunks_mut(CONVERSION_CHUNK_LEN)
+            .zip(self.data.par_chunks(CONVERSION_CHUNK_LEN))
+            .for_each(|(dst, src)| H::convert_to_f32_slice(src, dst));
+        Matrix::from_parts(
+            self.num_rows,
+            self.num_columns,
+            self.leading_dimension,
+            data,
+            self.layout,
+        )
+    }
+
+    /// Converts `matrix` into a half-precision matrix, rounding each
+    /// element to the nearest representable value.
+    pub fn from_f32(matrix: &Matrix<f32>) -> Self
+    where
+        H: Default,
+    {
+        let mut data = vec![H::default(); matrix.data.len()];
+        data.par_chunks_mut(CONVERSION_CHUNK_LEN)
+            .zip(matrix.data.par_chunks(CONVERSION_CHUNK_LEN))
+            .for_each(|(dst, src)| H::convert_from_f32_slice(src, dst));
+        Matrix::from_parts(
+            matrix.num_rows,
+            matrix.num_columns,
+            matrix.leading_dimension,
+            data,
+            matrix.layout,
+        )
+    }
+
+    /// Multiplies matrix `a` by matrix `b`, producing `c = a * b` in single
+    /// precision.
+    ///
+    /// The elements of `a` and `b` are converted to `f32` before they are
+    /// multiplied, and the products are accumulated in `f32`, so `c` is as
+    /// accurate as the product of `a.to_f32()` and `b.to_f32()` without the
+    /// cost of storing both conversions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a.num_columns() != b.num_rows()`.
+    pub fn mul_f32(a: &Self, b: &Self) -> Matrix<f32> {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(a_num_columns: usize, b_num_rows: usize) -> ! {
+            panic!(
+                "`a.num_columns()` (is {a_num_columns}) \
+                should be equal to `b.num_rows()` (is {b_num_rows})"
+            );
+        }
+
+        if a.num_columns() != b.num_rows() {
+            assert_failed(a.num_columns(), b.num_rows());
+        }
+
+        // Every row of `b` is read once per row of `a`, so it is converted
+        // up front; each row of `a` is read only once.
+        let b = b.to_f32().into_layout(Layout::RowMajor);
+        let a = a.as_row_major();
+        let mut c: Matrix<f32> = Matrix::zeros(a.num_rows(), b.num_columns());
+
+        c.rows_mut()
+            .zip(a.rows())
+            .par_bridge()
+            .for_each(|(ci, ai)| {
+                let mut ai_f32 = vec![0f32; ai.len()];
+                H::convert_to_f32_slice(ai, &mut ai_f32);
+                b.rows().zip(ai_f32.iter()).for_each(|(bk, aik)| {
+                    ci.iter_mut().zip(bk.iter()).for_each(|(cij, bkj)| {
+                        (*cij) += (*aik) * (*bkj);
+                    })
+                })
+            });
+        c
+    }
+}
+
+/// Number of elements converted by each task of the bulk conversions.
+const CONVERSION_CHUNK_LEN: usize = 1 << 14;
+
+#[cfg(test)]
+mod test_half {
+    use crate::{bf16, f16, Matrix};
+
+    #[test]
+    fn to_f32() {
+        let a: Matrix<f16> = Matrix::from([[0.5, 1.], [-2., 3.]].map(|row| row.map(f16::from_f32)));
+
+        assert_eq!(a.to_f32(), Matrix::from([[0.5, 1.], [-2., 3.]]));
+    }
+
+    #[test]
+    fn from_f32() {
+        let a: Matrix<f32> = Matrix::from([[1., 1. + f32::EPSILON], [65504., 1e6]]);
+        let a_f16: Matrix<f16> = Matrix::from_f32(&a);
+        let a_bf16: Matrix<bf16> = Matrix::from_f32(&a);
+
+        assert_eq!(
+            a_f16.to_f32(),
+            Matrix::from([[1., 1.], [65504., f32::INFINITY]])
+        );
+        assert_eq!(a_bf16.to_f32(), Matrix::from([[1., 1.], [65536., 999424.]]));
+    }
+
+    #[test]
+    fn mul_f32() {
+        let a: Matrix<bf16> = Matrix::from_f32(&Matrix::from([[0., 1.], [2., 3.], [4., 5.]]));
+        let b: Matrix<bf16> = Matrix::from_f32(&Matrix::from([[6.], [7.]]));
+        let c = Matrix::mul_f32(&a, &b);
+
+        assert_eq!(c, Matrix::from([[7.], [33.], [59.]]));
+    }
+
+    #[test]
+    fn mul_f32_accumulates_in_single_precision() {
+        // 4096 + 1 is not representable in `f16`, so accumulating in `f16`
+        // would get stuck at 2048.
+        let a: Matrix<f16> = Matrix::ones(1, 4097);
+        let b: Matrix<f16> = Matrix::ones(4097, 1);
+
+        assert_eq!(Matrix::mul_f32(&a, &b), Matrix::from([[4097.]]));
+        assert_eq!(Matrix::mul(&a, &b), Matrix::from([[f16::from_f32(2048.)]]));
+    }
+}
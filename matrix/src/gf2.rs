@@ -0,0 +1,407 @@
+use super::{Matrix, ModP};
+use rayon::prelude::*;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A matrix over GF(2), with each row packed into 64-bit words.
+///
+/// Addition in GF(2) is `XOR` and multiplication is `AND`, so the
+/// elimination methods of `BitMatrix` process 64 columns per instruction.
+/// Use [`Matrix<ModP<2>>`] for an unpacked representation.
+///
+/// [`Matrix<ModP<2>>`]: super::Matrix
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    words: Vec<u64>,
+    num_rows: usize,
+    num_columns: usize,
+    words_per_row: usize,
+}
+
+impl BitMatrix {
+    /// Creates a `BitMatrix` with shape `(num_rows, num_columns)`, filled
+    /// with zeros.
+    pub fn zeros(num_rows: usize, num_columns: usize) -> Self {
+        let words_per_row = num_columns.div_ceil(WORD_BITS);
+        Self {
+            words: vec![0; num_rows * words_per_row],
+            num_rows,
+            num_columns,
+            words_per_row,
+        }
+    }
+
+    /// Creates the `n`-by-`n` identity `BitMatrix`.
+    pub fn identity(n: usize) -> Self {
+        let mut matrix = Self::zeros(n, n);
+        (0..n).for_each(|i| matrix.set(i, i, true));
+        matrix
+    }
+
+    /// Returns the number of rows in the matrix.
+    #[inline]
+    pub const fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Returns the number of columns in the matrix.
+    #[inline]
+    pub const fn num_columns(&self) -> usize {
+        self.num_columns
+    }
+
+    /// Returns the shape `(num_rows, num_columns)` of the matrix.
+    #[inline]
+    pub const fn shape(&self) -> (usize, usize) {
+        (self.num_rows, self.num_columns)
+    }
+
+    /// Returns the element in row `i` and column `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of bounds.
+    #[inline]
+    pub fn get(&self, i: usize, j: usize) -> bool {
+        self.assert_in_bounds(i, j);
+        self.row(i)[j / WORD_BITS] >> (j % WORD_BITS) & 1 == 1
+    }
+
+    /// Sets the element in row `i` and column `j` to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of bounds.
+    #[inline]
+    pub fn set(&mut self, i: usize, j: usize, value: bool) {
+        self.assert_in_bounds(i, j);
+        let word = &mut self.row_mut(i)[j / WORD_BITS];
+        let mask = 1 << (j % WORD_BITS);
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Multiplies matrix `a` by matrix `b` over GF(2), producing
+    /// `c = a * b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a.num_columns() != b.num_rows()`.
+    pub fn mul(a: &Self, b: &Self) -> Self {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(a_num_columns: usize, b_num_rows: usize) -> ! {
+            panic!(
+                "`a.num_columns()` (is {a_num_columns}) \
+                should be equal to `b.num_rows()` (is {b_num_rows})"
+            );
+        }
+
+        if a.num_columns() != b.num_rows() {
+            assert_failed(a.num_columns(), b.num_rows());
+        }
+
+        let mut c = Self::zeros(a.num_rows(), b.num_columns());
+        if c.words_per_row == 0 {
+            return c;
+        }
+
+        // Row `i` of `c` is the sum of the rows of `b` selected by the bits
+        // set in row `i` of `a`.
+        c.words
+            .par_chunks_mut(c.words_per_row)
+            .zip(a.words.par_chunks(a.words_per_row.max(1)))
+            .for_each(|(ci, ai)| {
+                for k in
+                    (0..a.num_columns).filter(|k| ai[k / WORD_BITS] >> (k % WORD_BITS) & 1 == 1)
+                {
+                    ci.iter_mut()
+                        .zip(b.row(k))
+                        .for_each(|(cij, bkj)| *cij ^= bkj);
+                }
+            });
+        c
+    }
+
+    /// Returns the rank of the matrix over GF(2).
+    pub fn rank(&self) -> usize {
+        self.clone().reduce_rows().len()
+    }
+
+    /// Returns the determinant of the matrix over GF(2), which is `true`
+    /// if and only if the matrix is invertible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn determinant(&self) -> bool {
+        super::exact::assert_square(self.num_rows, self.num_columns);
+        self.rank() == self.num_rows
+    }
+
+    /// Returns the inverse of the matrix over GF(2), or `None` if it is
+    /// singular.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn inverse(&self) -> Option<Self> {
+        super::exact::assert_square(self.num_rows, self.num_columns);
+
+        // Reduces the augmented matrix `[A | I]` to `[I | A^-1]`.
+        let n = self.num_rows;
+        let mut augmented = self.augment(&Self::identity(n));
+        if augmented.reduce_rows().into_iter().take(n).ne(0..n) {
+            return None;
+        }
+
+        let mut inverse = Self::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                inverse.set(i, j, augmented.get(i, n + j));
+            }
+        }
+        Some(inverse)
+    }
+
+    /// Returns a basis of the null space of the matrix over GF(2), that is,
+    /// of the solutions of `A x = 0`.
+    ///
+    /// Each basis vector has `num_columns` elements.
+    pub fn null_space(&self) -> Vec<Vec<bool>> {
+        let mut reduced = self.clone();
+        let pivots = reduced.reduce_rows();
+
+        let mut is_pivot = vec![false; self.num_columns];
+        pivots.iter().for_each(|&c| is_pivot[c] = true);
+
+        (0..self.num_columns)
+            .filter(|&free| !is_pivot[free])
+            .map(|free| {
+                let mut vector = vec![false; self.num_columns];
+                vector[free] = true;
+                for (r, &c) in pivots.iter().enumerate() {
+                    vector[c] = reduced.get(r, free);
+                }
+                vector
+            })
+            .collect()
+    }
+
+    /// Solves `A x = b` over GF(2), returning `None` if the system is
+    /// inconsistent.
+    ///
+    /// If the system has many solutions, the one whose free variables are
+    /// all zero is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b.len() != self.num_rows()`.
+    pub fn solve(&self, b: &[bool]) -> Option<Vec<bool>> {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(b_len: usize, num_rows: usize) -> ! {
+            panic!("`b.len()` (is {b_len}) should be equal to `num_rows` (is {num_rows})");
+        }
+
+        if b.len() != self.num_rows {
+            assert_failed(b.len(), self.num_rows);
+        }
+
+        let mut column = Self::zeros(self.num_rows, 1);
+        b.iter()
+            .enumerate()
+            .for_each(|(i, &bi)| column.set(i, 0, bi));
+
+        let num_columns = self.num_columns;
+        let mut augmented = self.augment(&column);
+        let pivots = augmented.reduce_rows();
+        if pivots.last() == Some(&num_columns) {
+            return None;
+        }
+
+        let mut x = vec![false; num_columns];
+        for (r, &c) in pivots.iter().enumerate() {
+            x[c] = augmented.get(r, num_columns);
+        }
+        Some(x)
+    }
+
+    #[inline]
+    fn row(&self, i: usize) -> &[u64] {
+        &self.words[i * self.words_per_row..(i + 1) * self.words_per_row]
+    }
+
+    #[inline]
+    fn row_mut(&mut self, i: usize) -> &mut [u64] {
+        &mut self.words[i * self.words_per_row..(i + 1) * self.words_per_row]
+    }
+
+    #[inline]
+    #[track_caller]
+    fn assert_in_bounds(&self, i: usize, j: usize) {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(index: (usize, usize), shape: (usize, usize)) -> ! {
+            panic!("index {index:?} is out of bounds for a matrix with shape {shape:?}");
+        }
+
+        if i >= self.num_rows || j >= self.num_columns {
+            assert_failed((i, j), self.shape());
+        }
+    }
+
+    /// Returns the matrix `[self | other]`.
+    fn augment(&self, other: &Self) -> Self {
+        let mut augmented = Self::zeros(self.num_rows, self.num_columns + other.num_columns);
+        for i in 0..self.num_rows {
+            for j in 0..self.num_columns {
+                augmented.set(i, j, self.get(i, j));
+            }
+            for j in 0..other.num_columns {
+                augmented.set(i, self.num_columns + j, other.get(i, j));
+            }
+        }
+        augmented
+    }
+
+    /// Reduces the matrix to reduced row echelon form in place, returning
+    /// its pivot columns in increasing order.
+    fn reduce_rows(&mut self) -> Vec<usize> {
+        let words_per_row = self.words_per_row;
+        let mut pivots = Vec::new();
+
+        for c in 0..self.num_columns {
+            let r = pivots.len();
+            if r == self.num_rows {
+                break;
+            }
+            let (word, mask) = (c / WORD_BITS, 1 << (c % WORD_BITS));
+            let Some(p) = (r..self.num_rows).find(|&p| self.row(p)[word] & mask != 0) else {
+                continue;
+            };
+            if p != r {
+                let (head, tail) = self.words.split_at_mut(p * words_per_row);
+                head[r * words_per_row..(r + 1) * words_per_row]
+                    .swap_with_slice(&mut tail[..words_per_row]);
+            }
+
+            let pivot_row = self.row(r).to_vec();
+            self.words
+                .par_chunks_mut(words_per_row)
+                .enumerate()
+                .filter(|(i, row)| *i != r && row[word] & mask != 0)
+                .for_each(|(_, row)| {
+                    // Columns before `word` are zero in the pivot row.
+                    row[word..]
+                        .iter_mut()
+                        .zip(&pivot_row[word..])
+                        .for_each(|(x, y)| *x ^= y);
+                });
+            pivots.push(c);
+        }
+        pivots
+    }
+}
+
+impl From<&Matrix<ModP<2>>> for BitMatrix {
+    /// Creates a `BitMatrix` with the same shape as `matrix` and packs
+    /// `matrix`'s items into it.
+    fn from(matrix: &Matrix<ModP<2>>) -> Self {
+        let mut bits = Self::zeros(matrix.num_rows(), matrix.num_columns());
+        for i in 0..matrix.num_rows() {
+            for j in 0..matrix.num_columns() {
+                bits.set(i, j, matrix[(i, j)].value() == 1);
+            }
+        }
+        bits
+    }
+}
+
+impl From<&BitMatrix> for Matrix<ModP<2>> {
+    /// Creates a `Matrix<ModP<2>>` with the same shape as `bits` and unpacks
+    /// `bits`'s items into it.
+    fn from(bits: &BitMatrix) -> Self {
+        let mut matrix: Matrix<ModP<2>> = Matrix::zeros(bits.num_rows(), bits.num_columns());
+        for (i, row) in matrix.rows_mut().enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                *x = ModP::new(bits.get(i, j) as u64);
+            }
+        }
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod test_bit_matrix {
+    use super::BitMatrix;
+    use crate::{Matrix, ModP};
+
+    fn bits<const N: usize, const M: usize>(array: [[u64; N]; M]) -> BitMatrix {
+        BitMatrix::from(&Matrix::from(array.map(|row| row.map(ModP::<2>::new))))
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut matrix = BitMatrix::zeros(2, 130);
+        matrix.set(1, 129, true);
+        matrix.set(0, 64, true);
+        matrix.set(0, 64, false);
+
+        assert!(matrix.get(1, 129));
+        assert!(!matrix.get(0, 64));
+        assert_eq!(matrix.shape(), (2, 130));
+    }
+
+    #[test]
+    #[should_panic(expected = "index (2, 0) is out of bounds for a matrix with shape (2, 3)")]
+    fn get_out_of_bounds() {
+        let _ = BitMatrix::zeros(2, 3).get(2, 0);
+    }
+
+    #[test]
+    fn mul() {
+        let a: Matrix<ModP<2>> = Matrix::from([[1, 1, 0], [0, 1, 1]].map(|row| row.map(ModP::new)));
+        let b: Matrix<ModP<2>> =
+            Matrix::from([[1, 0], [1, 1], [0, 1]].map(|row| row.map(ModP::new)));
+        let c = BitMatrix::mul(&BitMatrix::from(&a), &BitMatrix::from(&b));
+
+        assert_eq!(Matrix::from(&c), Matrix::mul(&a, &b));
+    }
+
+    #[test]
+    fn rank_and_determinant() {
+        let a = bits([[1, 1, 0], [0, 1, 1], [1, 0, 1]]);
+
+        assert_eq!(a.rank(), 2);
+        assert!(!a.determinant());
+        assert!(BitMatrix::identity(70).determinant());
+    }
+
+    #[test]
+    fn inverse() {
+        let a = bits([[1, 1, 0], [0, 1, 1], [0, 0, 1]]);
+        let inverse = a.inverse().unwrap();
+
+        assert_eq!(BitMatrix::mul(&a, &inverse), BitMatrix::identity(3));
+        assert_eq!(bits([[1, 1], [1, 1]]).inverse(), None);
+    }
+
+    #[test]
+    fn null_space_and_solve() {
+        let a = bits([[1, 1, 0], [0, 1, 1], [1, 0, 1]]);
+
+        assert_eq!(a.null_space(), vec![vec![true, true, true]]);
+        assert_eq!(
+            a.solve(&[true, true, false]),
+            Some(vec![false, true, false])
+        );
+        assert_eq!(a.solve(&[true, false, false]), None);
+    }
+}
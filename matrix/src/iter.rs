@@ -8,18 +8,37 @@
 #[derive(Clone, Debug)]
 pub struct Rows<'a, T: 'a> {
     slice: &'a [T],
+    num_rows: usize,
     num_columns: usize,
+    stride: usize,
 }
 
 impl<'a, T: 'a> Rows<'a, T> {
     #[inline]
     pub(super) fn new(slice: &'a [T], num_columns: usize) -> Self {
-        Self { slice, num_columns }
+        let num_rows = slice.len().checked_div(num_columns).unwrap_or(0);
+        Self::with_stride(slice, num_rows, num_columns, num_columns)
     }
 
+    /// Creates an iterator over the `num_rows` rows of `slice`, which start
+    /// `stride` elements apart. The `stride - num_columns` elements after
+    /// each row are padding and are skipped.
+    ///
+    /// The number of rows is given rather than computed from the length of
+    /// `slice`, as rows of zero columns take no room in it.
     #[inline]
-    fn len(&self) -> usize {
-        self.slice.len() / self.num_columns
+    pub(super) fn with_stride(
+        slice: &'a [T],
+        num_rows: usize,
+        num_columns: usize,
+        stride: usize,
+    ) -> Self {
+        Self {
+            slice,
+            num_rows,
+            num_columns,
+            stride,
+        }
     }
 }
 
@@ -28,51 +47,49 @@ impl<'a, T> Iterator for Rows<'a, T> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.slice.is_empty() {
+        if self.num_rows == 0 {
             None
         } else {
-            let (head, tail) = self.slice.split_at(self.num_columns);
+            let (head, tail) = self.slice.split_at(self.stride);
             self.slice = tail;
-            Some(head)
+            self.num_rows -= 1;
+            Some(&head[..self.num_columns])
         }
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        if self.slice.is_empty() {
-            (0, Some(0))
-        } else {
-            let result = self.len();
-            (result, Some(result))
-        }
+        (self.num_rows, Some(self.num_rows))
     }
 
     #[inline]
     fn count(self) -> usize {
-        self.len()
+        self.num_rows
     }
 
     #[inline]
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        if n > self.len() {
+        if n >= self.num_rows {
             self.slice = &[];
+            self.num_rows = 0;
             None
         } else {
-            let start = n * self.num_columns;
-            let end = start + self.num_columns;
-            let nth = &self.slice[start..end];
+            let start = n * self.stride;
+            let end = start + self.stride;
+            let nth = &self.slice[start..(start + self.num_columns)];
             self.slice = &self.slice[end..];
+            self.num_rows -= n + 1;
             Some(nth)
         }
     }
 
     #[inline]
     fn last(self) -> Option<Self::Item> {
-        if self.slice.is_empty() {
+        if self.num_rows == 0 {
             None
         } else {
-            let start = self.slice.len() - self.num_columns;
-            let last = &self.slice[start..];
+            let start = (self.num_rows - 1) * self.stride;
+            let last = &self.slice[start..(start + self.num_columns)];
             Some(last)
         }
     }
@@ -88,18 +105,34 @@ impl<'a, T> Iterator for Rows<'a, T> {
 #[derive(Debug)]
 pub struct RowsMut<'a, T: 'a> {
     slice: &'a mut [T],
+    num_rows: usize,
     num_columns: usize,
+    stride: usize,
 }
 
 impl<'a, T: 'a> RowsMut<'a, T> {
     #[inline]
     pub(super) fn new(slice: &'a mut [T], num_columns: usize) -> Self {
-        Self { slice, num_columns }
+        let num_rows = slice.len().checked_div(num_columns).unwrap_or(0);
+        Self::with_stride(slice, num_rows, num_columns, num_columns)
     }
 
+    /// Creates an iterator over the `num_rows` rows of `slice`, which start
+    /// `stride` elements apart. The `stride - num_columns` elements after
+    /// each row are padding and are skipped.
     #[inline]
-    fn len(&self) -> usize {
-        self.slice.len() / self.num_columns
+    pub(super) fn with_stride(
+        slice: &'a mut [T],
+        num_rows: usize,
+        num_columns: usize,
+        stride: usize,
+    ) -> Self {
+        Self {
+            slice,
+            num_rows,
+            num_columns,
+            stride,
+        }
     }
 }
 
@@ -108,58 +141,56 @@ impl<'a, T> Iterator for RowsMut<'a, T> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.slice.is_empty() {
+        if self.num_rows == 0 {
             None
         } else {
             let slice = std::mem::take(&mut self.slice);
-            let (head, tail) = slice.split_at_mut(self.num_columns);
+            let (head, tail) = slice.split_at_mut(self.stride);
             self.slice = tail;
-            Some(head)
+            self.num_rows -= 1;
+            Some(&mut head[..self.num_columns])
         }
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        if self.slice.is_empty() {
-            (0, Some(0))
-        } else {
-            let result = self.len();
-            (result, Some(result))
-        }
+        (self.num_rows, Some(self.num_rows))
     }
 
     #[inline]
     fn count(self) -> usize {
-        self.len()
+        self.num_rows
     }
 
     #[inline]
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        if n > self.len() {
+        if n >= self.num_rows {
             self.slice = &mut [];
+            self.num_rows = 0;
             None
         } else {
-            let start = n * self.num_columns;
-            let end = start + self.num_columns;
+            let start = n * self.stride;
+            let end = start + self.stride;
 
             let slice = std::mem::take(&mut self.slice);
             let (head, tail) = slice.split_at_mut(end);
             self.slice = tail;
+            self.num_rows -= n + 1;
 
             let (_, nth) = head.split_at_mut(start);
-            Some(nth)
+            Some(&mut nth[..self.num_columns])
         }
     }
 
     #[inline]
     fn last(self) -> Option<Self::Item> {
-        if self.slice.is_empty() {
+        if self.num_rows == 0 {
             None
         } else {
-            let start = self.slice.len() - self.num_columns;
+            let start = (self.num_rows - 1) * self.stride;
             let slice = self.slice;
             let (_, last) = slice.split_at_mut(start);
-            Some(last)
+            Some(&mut last[..self.num_columns])
         }
     }
 }
@@ -221,6 +252,40 @@ mod test_rows {
 
         assert_eq!(rows.last(), Some([4, 5].as_slice()));
     }
+
+    #[test]
+    fn with_stride() {
+        let data = [0, 1, -1, 2, 3, -1, 4, 5, -1];
+        let num_columns = 2;
+        let stride = 3;
+
+        assert!(Rows::with_stride(&data, 3, num_columns, stride).eq([[0, 1], [2, 3], [4, 5]]));
+        assert_eq!(Rows::with_stride(&data, 3, num_columns, stride).count(), 3);
+        assert_eq!(
+            Rows::with_stride(&data, 3, num_columns, stride).nth(1),
+            Some([2, 3].as_slice())
+        );
+        assert_eq!(
+            Rows::with_stride(&data, 3, num_columns, stride).nth(3),
+            None
+        );
+        assert_eq!(
+            Rows::with_stride(&data, 3, num_columns, stride).last(),
+            Some([4, 5].as_slice())
+        );
+    }
+
+    #[test]
+    fn empty_rows() {
+        let data: [i32; 0] = [];
+        let mut rows = Rows::with_stride(&data, 3, 0, 0);
+
+        assert_eq!(rows.size_hint(), (3, Some(3)));
+        assert_eq!(rows.next(), Some([].as_slice()));
+        assert_eq!(rows.nth(1), Some([].as_slice()));
+        assert_eq!(rows.next(), None);
+        assert_eq!(Rows::new(&data, 0).count(), 0);
+    }
 }
 
 #[cfg(test)]
@@ -241,6 +306,7 @@ mod test_rows_mut {
     }
 
     #[test]
+    #[allow(clippy::iter_nth_zero)]
     fn mutability() {
         let mut data = [0, 1, 2, 3, 4, 5];
         let num_columns = 2;
@@ -298,4 +364,19 @@ mod test_rows_mut {
 
         assert_eq!(rows_mut.last(), Some([4, 5].as_mut_slice()));
     }
+
+    #[test]
+    fn with_stride() {
+        let mut data = [0, 1, -1, 2, 3, -1, 4, 5, -1];
+        let num_columns = 2;
+        let stride = 3;
+
+        RowsMut::with_stride(&mut data, 3, num_columns, stride).for_each(|row| row.fill(7));
+        RowsMut::with_stride(&mut data, 3, num_columns, stride)
+            .nth(1)
+            .unwrap()
+            .fill(8);
+
+        assert_eq!(data, [7, 7, -1, 8, 8, -1, 7, 7, -1]);
+    }
 }
@@ -0,0 +1,490 @@
+use super::iter::{Rows, RowsMut};
+use super::{Matrix, Storage, StorageMut};
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+/// The order in which the elements of a [`Matrix`] are stored in memory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// Rows are contiguous: element `(i, j)` is stored at
+    /// `i * num_columns + j`. This is the order of C and NumPy.
+    #[default]
+    RowMajor,
+    /// Columns are contiguous: element `(i, j)` is stored at
+    /// `j * num_rows + i`. This is the order of Fortran, BLAS and LAPACK.
+    ColumnMajor,
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: Storage<T>,
+{
+    /// Creates a `Matrix<T, S>` with shape `(num_rows, num_columns)` that
+    /// takes ownership of `data`, whose elements are in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data.len() != num_rows * num_columns`.
+    #[track_caller]
+    pub fn from_row_major(num_rows: usize, num_columns: usize, data: S) -> Self {
+        Self::from_layout(num_rows, num_columns, data, Layout::RowMajor)
+    }
+
+    /// Creates a `Matrix<T, S>` with shape `(num_rows, num_columns)` that
+    /// takes ownership of `data`, whose elements are in column-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data.len() != num_rows * num_columns`.
+    #[track_caller]
+    pub fn from_column_major(num_rows: usize, num_columns: usize, data: S) -> Self {
+        Self::from_layout(num_rows, num_columns, data, Layout::ColumnMajor)
+    }
+
+    #[track_caller]
+    pub(crate) fn from_layout(
+        num_rows: usize,
+        num_columns: usize,
+        data: S,
+        layout: Layout,
+    ) -> Self {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_len_failed(data_len: usize, num_elements: usize) -> ! {
+            panic!(
+                "`data.len()` (is {data_len}) \
+                should be equal to `num_rows * num_columns` (is {num_elements})"
+            );
+        }
+
+        let data_len = data.as_slice().len();
+        if data_len != num_rows * num_columns {
+            assert_len_failed(data_len, num_rows * num_columns);
+        }
+        let leading_dimension = match layout {
+            Layout::RowMajor => num_columns,
+            Layout::ColumnMajor => num_rows,
+        };
+        Self::from_parts(num_rows, num_columns, leading_dimension, data, layout)
+    }
+
+    /// Creates a row-major `Matrix<T, S>` whose rows start
+    /// `leading_dimension` elements apart in `data`.
+    #[track_caller]
+    pub(crate) fn from_padded(
+        num_rows: usize,
+        num_columns: usize,
+        leading_dimension: usize,
+        data: S,
+    ) -> Self {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_leading_dimension_failed(leading_dimension: usize, num_columns: usize) -> ! {
+            panic!(
+                "`leading_dimension` (is {leading_dimension}) \
+                should be >= `num_columns` (is {num_columns})"
+            );
+        }
+
+        if leading_dimension < num_columns {
+            assert_leading_dimension_failed(leading_dimension, num_columns);
+        }
+        debug_assert_eq!(data.as_slice().len(), num_rows * leading_dimension);
+        Self::from_parts(
+            num_rows,
+            num_columns,
+            leading_dimension,
+            data,
+            Layout::RowMajor,
+        )
+    }
+
+    /// Creates a `Matrix<T, S>` from its parts, which the caller has
+    /// already validated: `data` holds `leading_dimension` elements for
+    /// each lane.
+    #[inline]
+    pub(crate) fn from_parts(
+        num_rows: usize,
+        num_columns: usize,
+        leading_dimension: usize,
+        data: S,
+        layout: Layout,
+    ) -> Self {
+        Self {
+            data,
+            num_rows,
+            num_columns,
+            leading_dimension,
+            layout,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the order in which the elements of the matrix are stored.
+    #[inline]
+    pub const fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Returns a `Matrix<T>` that owns a copy of the elements of the matrix
+    /// stored in `layout` order, without padding.
+    pub fn to_layout(&self, layout: Layout) -> Matrix<T>
+    where
+        T: Clone,
+    {
+        let items = self.data.as_slice();
+        let data = self
+            .positions(layout)
+            .map(|position| items[position].clone())
+            .collect();
+        Matrix::from_layout(self.num_rows, self.num_columns, data, layout)
+    }
+
+    /// An iterator over the columns of a column-major matrix. The columns
+    /// are slices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not column-major.
+    #[track_caller]
+    pub fn columns(&self) -> Rows<'_, T> {
+        self.assert_layout(Layout::ColumnMajor, "columns");
+        self.lanes()
+    }
+
+    /// Returns a copy of the elements of the matrix in row-major order.
+    pub(crate) fn to_row_major_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.to_layout(Layout::RowMajor).data
+    }
+
+    /// Returns the number of contiguous lanes, rows or columns, in which
+    /// the elements are stored.
+    #[inline]
+    pub(crate) const fn num_lanes(&self) -> usize {
+        match self.layout {
+            Layout::RowMajor => self.num_rows,
+            Layout::ColumnMajor => self.num_columns,
+        }
+    }
+
+    /// Returns the number of elements in each contiguous lane.
+    #[inline]
+    pub(crate) const fn lane_len(&self) -> usize {
+        match self.layout {
+            Layout::RowMajor => self.num_columns,
+            Layout::ColumnMajor => self.num_rows,
+        }
+    }
+
+    /// An iterator over the contiguous lanes, rows or columns, in which the
+    /// elements are stored.
+    #[inline]
+    pub(crate) fn lanes(&self) -> Rows<'_, T> {
+        Rows::with_stride(
+            self.data.as_slice(),
+            self.num_lanes(),
+            self.lane_len(),
+            self.leading_dimension,
+        )
+    }
+
+    /// Returns `true` if each lane is followed by padding.
+    #[inline]
+    pub(crate) const fn is_padded(&self) -> bool {
+        self.leading_dimension != self.lane_len()
+    }
+
+    /// An iterator over the positions in storage of the elements of the
+    /// matrix, in `layout` order, skipping the padding.
+    pub(crate) fn positions(&self, layout: Layout) -> impl Iterator<Item = usize> {
+        let (num_lanes, lane_len) = (self.num_lanes(), self.lane_len());
+        let stride = self.leading_dimension;
+        let transpose = layout != self.layout;
+        let (outer, inner) = if transpose {
+            (lane_len, num_lanes)
+        } else {
+            (num_lanes, lane_len)
+        };
+        (0..outer).flat_map(move |a| {
+            (0..inner).map(move |b| {
+                if transpose {
+                    b * stride + a
+                } else {
+                    a * stride + b
+                }
+            })
+        })
+    }
+
+    /// Returns the position in storage of the element `(i, j)`.
+    #[inline]
+    pub(crate) const fn offset(&self, i: usize, j: usize) -> usize {
+        match self.layout {
+            Layout::RowMajor => i * self.leading_dimension + j,
+            Layout::ColumnMajor => j * self.leading_dimension + i,
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub(crate) fn assert_layout(&self, layout: Layout, method_name: &str) {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(method_name: &str, expected: Layout, layout: Layout) -> ! {
+            panic!("`{method_name}` requires a {expected:?} matrix (is {layout:?})");
+        }
+
+        if self.layout != layout {
+            assert_failed(method_name, layout, self.layout);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    pub(crate) fn assert_row_in_bounds(&self, row_index: usize) {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(num_rows: usize, row_index: usize) -> ! {
+            panic!("index out of bounds: the len is {num_rows} but the index is {row_index}");
+        }
+
+        if row_index >= self.num_rows {
+            assert_failed(self.num_rows, row_index);
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    fn assert_in_bounds(&self, i: usize, j: usize) {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(index: (usize, usize), shape: (usize, usize)) -> ! {
+            panic!("index {index:?} is out of bounds for a matrix with shape {shape:?}");
+        }
+
+        if i >= self.num_rows || j >= self.num_columns {
+            assert_failed((i, j), self.shape());
+        }
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: StorageMut<T>,
+{
+    /// An iterator over the columns of a column-major matrix. The columns
+    /// are mutable slices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not column-major.
+    #[track_caller]
+    pub fn columns_mut(&mut self) -> RowsMut<'_, T> {
+        self.assert_layout(Layout::ColumnMajor, "columns_mut");
+        self.lanes_mut()
+    }
+
+    /// An iterator over the contiguous lanes, rows or columns, in which the
+    /// elements are stored. The lanes are mutable slices.
+    #[inline]
+    pub(crate) fn lanes_mut(&mut self) -> RowsMut<'_, T> {
+        let (num_lanes, lane_len) = (self.num_lanes(), self.lane_len());
+        let stride = self.leading_dimension;
+        RowsMut::with_stride(self.data.as_mut_slice(), num_lanes, lane_len, stride)
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Converts the matrix so that its elements are stored in `layout`
+    /// order, without padding, moving them instead of cloning them.
+    pub fn into_layout(self, layout: Layout) -> Self {
+        if layout == self.layout && !self.is_padded() {
+            return self;
+        }
+        let positions: Vec<usize> = self.positions(layout).collect();
+        let mut items: Vec<Option<T>> = self.data.into_iter().map(Some).collect();
+        let data = positions
+            .into_iter()
+            .map(|position| items[position].take().unwrap())
+            .collect();
+        Self::from_layout(self.num_rows, self.num_columns, data, layout)
+    }
+
+    /// Returns the matrix itself if it is row-major and not padded, or a
+    /// row-major copy of it otherwise.
+    pub(crate) fn as_row_major(&self) -> Cow<'_, Self>
+    where
+        T: Clone,
+    {
+        if self.layout == Layout::RowMajor && !self.is_padded() {
+            Cow::Borrowed(self)
+        } else {
+            Cow::Owned(self.to_layout(Layout::RowMajor))
+        }
+    }
+}
+
+impl<T, S> Index<(usize, usize)> for Matrix<T, S>
+where
+    S: Storage<T>,
+{
+    type Output = T;
+
+    /// Returns the element in row `i` and column `j`, whatever the layout
+    /// of the matrix.
+    #[inline]
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        self.assert_in_bounds(i, j);
+        &self.data.as_slice()[self.offset(i, j)]
+    }
+}
+
+impl<T, S> IndexMut<(usize, usize)> for Matrix<T, S>
+where
+    S: StorageMut<T>,
+{
+    #[inline]
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Self::Output {
+        self.assert_in_bounds(i, j);
+        let offset = self.offset(i, j);
+        &mut self.data.as_mut_slice()[offset]
+    }
+}
+
+impl<T, S, R> PartialEq<Matrix<T, R>> for Matrix<T, S>
+where
+    T: PartialEq,
+    S: Storage<T>,
+    R: Storage<T>,
+{
+    /// Two matrices are equal if they have the same shape and the same
+    /// elements, whatever their layouts and storages.
+    fn eq(&self, other: &Matrix<T, R>) -> bool {
+        if self.shape() != other.shape() {
+            return false;
+        }
+        if self.layout == other.layout {
+            return self.lanes().eq(other.lanes());
+        }
+        (0..self.num_rows).all(|i| (0..self.num_columns).all(|j| self[(i, j)] == other[(i, j)]))
+    }
+}
+
+impl<T: Eq, S: Storage<T>> Eq for Matrix<T, S> {}
+
+#[cfg(test)]
+mod test_layout {
+    use super::Layout;
+    use crate::Matrix;
+
+    fn column_major() -> Matrix<i32> {
+        Matrix::from_column_major(3, 2, vec![0, 2, 4, 1, 3, 5])
+    }
+
+    #[test]
+    fn from_row_major() {
+        let matrix = Matrix::from_row_major(3, 2, vec![0, 1, 2, 3, 4, 5]);
+
+        assert_eq!(matrix.layout(), Layout::RowMajor);
+        assert_eq!(
+            matrix.as_flattened(),
+            Matrix::from([[0, 1], [2, 3], [4, 5]]).as_flattened()
+        );
+    }
+
+    #[test]
+    fn from_column_major() {
+        let matrix = column_major();
+
+        assert_eq!(matrix.layout(), Layout::ColumnMajor);
+        assert_eq!(matrix.shape(), (3, 2));
+        assert_eq!(matrix[(0, 1)], 1);
+        assert_eq!(matrix[(2, 0)], 4);
+        assert_eq!(matrix.as_flattened(), [0, 2, 4, 1, 3, 5]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`data.len()` (is 5) should be equal to `num_rows * num_columns` (is 6)"
+    )]
+    fn from_column_major_with_invalid_len() {
+        let _ = Matrix::from_column_major(3, 2, vec![0; 5]);
+    }
+
+    #[test]
+    fn to_layout() {
+        let matrix = column_major();
+        let row_major = matrix.to_layout(Layout::RowMajor);
+
+        assert_eq!(row_major.layout(), Layout::RowMajor);
+        assert_eq!(row_major.as_flattened(), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(
+            row_major.to_layout(Layout::ColumnMajor).as_flattened(),
+            matrix.as_flattened()
+        );
+        assert_eq!(
+            matrix.clone().into_layout(Layout::RowMajor).as_flattened(),
+            row_major.as_flattened()
+        );
+    }
+
+    #[test]
+    fn partial_eq() {
+        let matrix = column_major();
+
+        assert_eq!(matrix, Matrix::from([[0, 1], [2, 3], [4, 5]]));
+        assert_ne!(matrix, Matrix::from([[0, 1], [2, 3], [4, 6]]));
+        assert_ne!(
+            matrix,
+            Matrix::from_column_major(2, 3, vec![0, 2, 4, 1, 3, 5])
+        );
+    }
+
+    #[test]
+    fn index_mut() {
+        let mut matrix = column_major();
+        matrix[(1, 1)] = 7;
+
+        assert_eq!(matrix, Matrix::from([[0, 1], [2, 7], [4, 5]]));
+    }
+
+    #[test]
+    #[should_panic(expected = "index (3, 0) is out of bounds for a matrix with shape (3, 2)")]
+    fn index_out_of_bounds() {
+        let _ = column_major()[(3, 0)];
+    }
+
+    #[test]
+    fn columns() {
+        let mut matrix = column_major();
+        let mut columns = matrix.columns();
+
+        assert_eq!(columns.next(), Some([0, 2, 4].as_slice()));
+        assert_eq!(columns.next(), Some([1, 3, 5].as_slice()));
+        assert_eq!(columns.next(), None);
+
+        matrix.columns_mut().last().unwrap().fill(0);
+        assert_eq!(matrix, Matrix::from([[0, 0], [2, 0], [4, 0]]));
+    }
+
+    #[test]
+    #[should_panic(expected = "`rows` requires a RowMajor matrix (is ColumnMajor)")]
+    fn rows_of_column_major_matrix() {
+        let _ = column_major().rows();
+    }
+
+    #[test]
+    #[should_panic(expected = "`columns` requires a ColumnMajor matrix (is RowMajor)")]
+    fn columns_of_row_major_matrix() {
+        let _ = Matrix::from([[0, 1]]).columns();
+    }
+}
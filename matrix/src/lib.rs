@@ -1,95 +1,180 @@
-//!
+//! A two-dimensional array library with a cache efficient, multithreaded
+//! matrix multiplication.
 #![allow(dead_code)]
 
+mod approximate;
+mod binary;
+mod block;
+mod broadcast;
+mod csv;
+mod display;
+mod elementwise;
+mod error;
+mod exact;
+#[cfg(feature = "half")]
+mod float16;
+mod gf2;
 mod iter;
+mod layout;
+mod macros;
+mod market;
+mod modular;
+mod norm;
+mod npy;
+#[cfg(feature = "npz")]
+mod npz;
 mod oper;
+mod precision;
+mod random;
+#[cfg(feature = "rational")]
+mod rational;
+mod reduce;
+mod reshape;
+#[cfg(feature = "serde")]
+mod serialize;
+mod smatrix;
+mod storage;
+mod structured;
+
+#[doc(hidden)]
+pub use approximate::assert_approx_eq as __assert_approx_eq;
+pub use approximate::Ulps;
+#[cfg(feature = "mmap")]
+pub use binary::MmapStorage;
+pub use broadcast::Broadcast;
+pub use csv::{CsvOptions, Missing};
+pub use display::{MatrixDisplay, PrintOptions};
+pub use error::MatrixError;
+pub use exact::{Field, SignedInteger};
+#[cfg(feature = "half")]
+pub use float16::HalfFloat;
+pub use gf2::BitMatrix;
+#[cfg(feature = "half")]
+pub use half::{bf16, f16};
+pub use layout::Layout;
+pub use market::{MarketElement, MarketField, MarketFormat, Symmetry};
+pub use modular::ModP;
+pub use norm::Metric;
+pub use npy::NpyElement;
+#[cfg(feature = "npz")]
+pub use npz::{NpzReader, NpzWriter};
+#[cfg(feature = "rational")]
+pub use num_bigint::BigInt;
+#[cfg(feature = "complex")]
+pub use num_complex::Complex;
+#[cfg(feature = "rational")]
+pub use num_rational::{BigRational, Ratio};
+pub use precision::{Kahan, Pairwise, Precision, Standard};
+pub use reduce::Axis;
+pub use smatrix::SMatrix;
+pub use storage::{AlignedVec, Storage, StorageMut};
 
 use iter::Rows;
 use iter::RowsMut;
 use num_traits::{One, Zero};
+use std::fmt;
+use std::marker::PhantomData;
 use std::ops::{Index, IndexMut};
 
 /// A two-dimensional array type, written as `Matrix<T>`.
-#[derive(Debug, PartialEq)]
-pub struct Matrix<T> {
-    data: Vec<T>,
+///
+/// The elements are stored contiguously, row after row by default. See
+/// [`Layout`] for the alternative column-major order.
+///
+/// A `Matrix<T>` owns its elements in a `Vec<T>`. A `Matrix<T, S>` stores
+/// them in any other [`Storage`], such as a borrowed slice or an
+/// [`AlignedVec<T>`].
+#[derive(Clone)]
+pub struct Matrix<T, S = Vec<T>> {
+    data: S,
     num_rows: usize,
     num_columns: usize,
+    /// The distance, in elements, between the starts of two consecutive
+    /// rows, or columns if the matrix is column-major.
+    leading_dimension: usize,
+    layout: Layout,
+    marker: PhantomData<T>,
 }
 
 impl<T> Matrix<T> {
     /// Creates a `Matrix<T>` with shape `(num_rows, num_columns)`, filled
     /// with `fill_value`.
     ///
-    /// # Panics
-    ///
-    /// Panics if `num_rows` or `num_columns` equals zero.
+    /// Either dimension may be zero, in which case the matrix is empty.
     pub fn full(num_rows: usize, num_columns: usize, fill_value: T) -> Self
     where
-        T: Copy,
+        T: Clone,
     {
-        #[cold]
-        #[inline(never)]
-        #[track_caller]
-        fn assert_failed(dimension_name: &str) -> ! {
-            panic!("`num_{dimension_name}` (is 0) should be > 0");
-        }
-
-        if num_rows == 0 {
-            assert_failed("rows");
-        }
-        if num_columns == 0 {
-            assert_failed("columns");
-        }
         Self {
             data: vec![fill_value; num_rows * num_columns],
             num_rows,
             num_columns,
+            leading_dimension: num_columns,
+            layout: Layout::RowMajor,
+            marker: PhantomData,
         }
     }
 
     /// Creates a `Matrix<T>` with shape `(num_rows, num_columns)`, filled
     /// with the default value of `T`.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `num_rows` or `num_columns` equals zero.
     #[inline]
     pub fn new(num_rows: usize, num_columns: usize) -> Self
     where
-        T: Copy + Default,
+        T: Clone + Default,
     {
         Self::full(num_rows, num_columns, T::default())
     }
 
     /// Creates a `Matrix<T>` with shape `(num_rows, num_columns)`, filled
     /// with zeros.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `num_rows` or `num_columns` equals zero.
     #[inline]
     pub fn zeros(num_rows: usize, num_columns: usize) -> Self
     where
-        T: Copy + Zero,
+        T: Clone + Zero,
     {
         Self::full(num_rows, num_columns, T::zero())
     }
 
     /// Creates a `Matrix<T>` with shape `(num_rows, num_columns)`, filled
     /// with ones.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `num_rows` or `num_columns` equals zero.
     #[inline]
     pub fn ones(num_rows: usize, num_columns: usize) -> Self
     where
-        T: Copy + One,
+        T: Clone + One,
     {
         Self::full(num_rows, num_columns, T::one())
     }
 
+    /// Creates a row-major `Matrix<T>` with shape `(num_rows, num_columns)`,
+    /// filled with `fill_value`, whose rows start `leading_dimension`
+    /// elements apart.
+    ///
+    /// The `leading_dimension - num_columns` elements after each row are
+    /// padding: they are also set to `fill_value`, but are not part of the
+    /// matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `leading_dimension < num_columns`.
+    #[track_caller]
+    pub fn with_padding(
+        num_rows: usize,
+        num_columns: usize,
+        leading_dimension: usize,
+        fill_value: T,
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let data = vec![fill_value; num_rows * leading_dimension];
+        Self::from_padded(num_rows, num_columns, leading_dimension, data)
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: Storage<T>,
+{
     /// Returns the number of rows in the matrix.
     #[inline]
     pub const fn num_rows(&self) -> usize {
@@ -108,75 +193,159 @@ impl<T> Matrix<T> {
         (self.num_rows, self.num_columns)
     }
 
+    /// Returns the distance, in elements, between the starts of two
+    /// consecutive rows, or columns if the matrix is column-major.
+    ///
+    /// It is larger than the length of the rows when the matrix is padded,
+    /// as the ones created by [`with_padding`] and [`zeros_aligned`].
+    ///
+    /// [`with_padding`]: Matrix::with_padding
+    /// [`zeros_aligned`]: Matrix::zeros_aligned
+    #[inline]
+    pub const fn leading_dimension(&self) -> usize {
+        self.leading_dimension
+    }
+
     /// Extracts a slice containing the matrix flattened to one dimension.
+    ///
+    /// The elements are in the order given by the [`layout`] of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is padded. Use [`try_as_flattened`] to handle
+    /// padded matrices.
+    ///
+    /// [`layout`]: Matrix::layout
+    /// [`try_as_flattened`]: Matrix::try_as_flattened
     #[inline]
+    #[track_caller]
     pub fn as_flattened(&self) -> &[T] {
-        &self.data
+        match self.try_as_flattened() {
+            Ok(flattened) => flattened,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Extracts a slice containing the matrix flattened to one dimension,
+    /// or returns [`MatrixError::Padded`] if padding separates its rows.
+    ///
+    /// The elements are in the order given by the [`layout`] of the matrix.
+    ///
+    /// [`layout`]: Matrix::layout
+    #[inline]
+    pub fn try_as_flattened(&self) -> Result<&[T], MatrixError> {
+        if self.is_padded() {
+            return Err(MatrixError::Padded {
+                leading_dimension: self.leading_dimension,
+                lane_len: self.lane_len(),
+            });
+        }
+        Ok(self.data.as_slice())
     }
 
     /// An iterator over the rows of the matrix. The rows are slices.
     ///
     /// As a matrix consists of a sequence of rows, we can iterate through
     /// a matrix by row. This method returns such an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not row-major. Use [`columns`] to iterate
+    /// through a column-major matrix.
+    ///
+    /// [`columns`]: Matrix::columns
+    #[track_caller]
     pub fn rows(&self) -> Rows<'_, T> {
-        Rows::new(&self.data, self.num_columns)
+        self.assert_layout(Layout::RowMajor, "rows");
+        self.lanes()
     }
+}
 
+impl<T, S> Matrix<T, S>
+where
+    S: StorageMut<T>,
+{
     /// An iterator over the rows of the matrix. The rows are mutable slices.
     ///
     /// As a matrix consists of a sequence of rows, we can iterate through
     /// a matrix by row. This method returns such an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not row-major. Use [`columns_mut`] to
+    /// iterate through a column-major matrix.
+    ///
+    /// [`columns_mut`]: Matrix::columns_mut
+    #[track_caller]
     pub fn rows_mut(&mut self) -> RowsMut<'_, T> {
-        RowsMut::new(&mut self.data, self.num_columns)
+        self.assert_layout(Layout::RowMajor, "rows_mut");
+        self.lanes_mut()
     }
 }
 
 impl<T, const N: usize, const M: usize> From<[[T; N]; M]> for Matrix<T>
 where
-    T: Copy,
     [[T; N]; M]: Sized,
 {
-    /// Creates a `Matrix<T>` with shape `(M, N)` and copies `array`'s items
+    /// Creates a `Matrix<T>` with shape `(M, N)` and moves `array`'s items
     /// into it.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `M` or `N` equals zero.
     fn from(array: [[T; N]; M]) -> Matrix<T> {
-        #[cold]
-        #[inline(never)]
-        #[track_caller]
-        fn assert_failed(dimension_name: &str) -> ! {
-            panic!("`num_{dimension_name}` (is 0) should be > 0");
-        }
-
-        if M == 0 {
-            assert_failed("rows");
-        }
-        if N == 0 {
-            assert_failed("columns");
-        }
         Self {
             data: array.into_iter().flatten().collect::<Vec<T>>(),
             num_rows: M,
             num_columns: N,
+            leading_dimension: N,
+            layout: Layout::RowMajor,
+            marker: PhantomData,
         }
     }
 }
 
-impl<T> Index<usize> for Matrix<T> {
+impl<T, S> fmt::Debug for Matrix<T, S>
+where
+    T: fmt::Debug,
+    S: Storage<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Matrix")
+            .field("data", &self.data.as_slice())
+            .field("num_rows", &self.num_rows)
+            .field("num_columns", &self.num_columns)
+            .field("layout", &self.layout)
+            .finish()
+    }
+}
+
+impl<T, S> Index<usize> for Matrix<T, S>
+where
+    S: Storage<T>,
+{
     type Output = [T];
 
+    /// Returns the row `row_index` of a row-major matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not row-major, or if `row_index` is out of
+    /// bounds. Use `matrix[(i, j)]` to access the elements of a
+    /// column-major matrix.
     fn index(&self, row_index: usize) -> &Self::Output {
-        let num_columns = self.num_columns;
-        &self.data[(row_index * num_columns)..((row_index + 1) * num_columns)]
+        self.assert_layout(Layout::RowMajor, "index");
+        self.assert_row_in_bounds(row_index);
+        let start = row_index * self.leading_dimension;
+        &self.data.as_slice()[start..(start + self.num_columns)]
     }
 }
 
-impl<T> IndexMut<usize> for Matrix<T> {
+impl<T, S> IndexMut<usize> for Matrix<T, S>
+where
+    S: StorageMut<T>,
+{
     fn index_mut(&mut self, row_index: usize) -> &mut Self::Output {
-        let num_columns = self.num_columns;
-        &mut self.data[(row_index * num_columns)..((row_index + 1) * num_columns)]
+        self.assert_layout(Layout::RowMajor, "index_mut");
+        self.assert_row_in_bounds(row_index);
+        let start = row_index * self.leading_dimension;
+        &mut self.data.as_mut_slice()[start..(start + self.num_columns)]
     }
 }
 
@@ -210,15 +379,21 @@ mod test_matrix {
     }
 
     #[test]
-    #[should_panic(expected = "`num_rows` (is 0) should be > 0")]
-    fn full_with_invalid_num_rows() {
-        let _: Matrix<f32> = Matrix::full(0, 0, 0.5);
+    fn full_with_zero_num_rows() {
+        let matrix: Matrix<f32> = Matrix::full(0, 3, 0.5);
+
+        assert_eq!(matrix.shape(), (0, 3));
+        assert_eq!(matrix.rows().count(), 0);
+        assert!(matrix.as_flattened().is_empty());
     }
 
     #[test]
-    #[should_panic(expected = "`num_columns` (is 0) should be > 0")]
-    fn full_with_invalid_num_columns() {
-        let _: Matrix<f32> = Matrix::full(1, 0, 0.5);
+    fn full_with_zero_num_columns() {
+        let matrix: Matrix<f32> = Matrix::full(2, 0, 0.5);
+
+        assert_eq!(matrix.shape(), (2, 0));
+        assert!(matrix.rows().eq([[0.; 0], []]));
+        assert_eq!(matrix[1], [0.; 0]);
     }
 
     #[test]
@@ -226,7 +401,7 @@ mod test_matrix {
         let num_rows = 2;
         let num_columns = 2;
         let matrix: Matrix<f32> = Matrix::new(num_rows, num_columns);
-        let expected_value = Default::default();
+        let expected_value: f32 = Default::default();
 
         for i in 0..num_rows {
             for j in 0..num_columns {
@@ -270,6 +445,37 @@ mod test_matrix {
         assert_eq!(matrix.as_flattened(), [0., 0., 0., 0.]);
     }
 
+    #[test]
+    fn with_padding() {
+        let mut matrix: Matrix<i32> = Matrix::with_padding(3, 2, 4, -1);
+        matrix.rows_mut().flatten().for_each(|x| *x = 0);
+        matrix[1][1] = 3;
+        matrix[(2, 0)] = 4;
+
+        assert_eq!(matrix.leading_dimension(), 4);
+        assert_eq!(matrix, Matrix::from([[0, 0], [0, 3], [4, 0]]));
+        assert!(matrix.rows().eq([[0, 0], [0, 3], [4, 0]]));
+        assert!(matrix.try_as_flattened().is_err());
+        assert_eq!(matrix.to_owned_matrix().as_flattened(), [0, 0, 0, 3, 4, 0]);
+        assert_eq!(
+            Matrix::mul(&matrix, &Matrix::from([[1], [1]])),
+            Matrix::from([[0], [3], [4]])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`leading_dimension` (is 1) should be >= `num_columns` (is 2)")]
+    fn with_invalid_padding() {
+        let _: Matrix<f32> = Matrix::with_padding(3, 2, 1, 0.);
+    }
+
+    #[test]
+    #[should_panic(expected = "the matrix is padded: its leading dimension (is 4) \
+                    is larger than its lanes (are 2 elements long)")]
+    fn as_flattened_with_padding() {
+        let _ = Matrix::with_padding(3, 2, 4, 0.).as_flattened();
+    }
+
     #[test]
     fn rows() {
         let matrix: Matrix<f32> = Matrix::from([[0., 1.], [2., 3.], [4., 5.]]);
@@ -282,6 +488,7 @@ mod test_matrix {
     }
 
     #[test]
+    #[allow(clippy::iter_nth_zero)]
     fn rows_mut() {
         let mut matrix: Matrix<f32> = Matrix::from([[0., 1.], [2., 3.], [4., 5.]]);
         let mut rows_mut = matrix.rows_mut();
@@ -298,10 +505,10 @@ mod test_matrix {
     #[test]
     fn from() {
         let matrix: Matrix<f32> = Matrix::from([[0., 1., 2.], [3., 4., 5.]]);
-    
+
         assert_eq!(matrix.num_rows(), 2);
         assert_eq!(matrix.num_columns(), 3);
-    
+
         let mut value: f32 = 0.;
         for i in 0..matrix.num_rows() {
             for j in 0..matrix.num_columns() {
@@ -312,15 +519,18 @@ mod test_matrix {
     }
 
     #[test]
-    #[should_panic(expected = "`num_rows` (is 0) should be > 0")]
-    fn from_with_invalid_num_rows() {
-        let _: Matrix<f32> = Matrix::from([[0f32; 3]; 0]);
+    fn from_empty_arrays() {
+        assert_eq!(Matrix::from([[0f32; 3]; 0]).shape(), (0, 3));
+        assert_eq!(Matrix::from([[0f32; 0]; 2]).shape(), (2, 0));
+        assert_eq!(Matrix::from([[0f32; 0]; 2]), Matrix::zeros(2, 0));
+        assert_ne!(Matrix::from([[0f32; 0]; 2]), Matrix::zeros(0, 2));
     }
 
     #[test]
-    #[should_panic(expected = "`num_columns` (is 0) should be > 0")]
-    fn from_with_invalid_num_columns() {
-        let _: Matrix<f32> = Matrix::from([[0f32; 0]; 2]);
+    #[should_panic(expected = "index out of bounds: the len is 2 but the index is 2")]
+    fn index_out_of_bounds_of_empty_rows() {
+        let matrix: Matrix<f32> = Matrix::zeros(2, 0);
+        let _ = &matrix[2];
     }
 
     #[test]
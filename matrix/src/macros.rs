@@ -0,0 +1,105 @@
+/// Creates an [`SMatrix`] from its elements, given row by row, with rows
+/// separated by semicolons and elements by commas.
+///
+/// `matrix![1, 2, 3; 4, 5, 6]` is equivalent to
+/// `SMatrix::from([[1, 2, 3], [4, 5, 6]])`, so the shape of the matrix is
+/// part of its type. See [`dmatrix!`] to create a [`Matrix`] instead.
+///
+/// # Examples
+///
+/// ```
+/// use matrix::{matrix, SMatrix};
+///
+/// let a = matrix![1., 2., 3.;
+///                 4., 5., 6.];
+///
+/// assert_eq!(a, SMatrix::from([[1., 2., 3.], [4., 5., 6.]]));
+/// assert_eq!(a.shape(), (2, 3));
+/// ```
+///
+/// Rows of different lengths are rejected at compile time:
+///
+/// ```compile_fail
+/// use matrix::matrix;
+///
+/// let a = matrix![1, 2, 3; 4, 5];
+/// ```
+///
+/// [`SMatrix`]: crate::SMatrix
+/// [`Matrix`]: crate::Matrix
+#[macro_export]
+macro_rules! matrix {
+    () => {
+        $crate::SMatrix::from([])
+    };
+    ($($($x:expr),+ $(,)?);+ $(;)?) => {
+        $crate::SMatrix::from([$([$($x),+]),+])
+    };
+}
+
+/// Creates a [`Matrix`] from its elements, given row by row, with rows
+/// separated by semicolons and elements by commas.
+///
+/// `dmatrix![1, 2, 3; 4, 5, 6]` is equivalent to
+/// `Matrix::from([[1, 2, 3], [4, 5, 6]])`. The elements may be of any type,
+/// and are moved into the matrix. As with [`matrix!`], rows of different
+/// lengths are rejected at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use matrix::{dmatrix, Matrix};
+///
+/// let a = dmatrix![1., 2., 3.;
+///                  4., 5., 6.];
+///
+/// assert_eq!(a, Matrix::from([[1., 2., 3.], [4., 5., 6.]]));
+/// assert_eq!(a.shape(), (2, 3));
+/// ```
+///
+/// ```compile_fail
+/// use matrix::dmatrix;
+///
+/// let a = dmatrix![1, 2; 3, 4; 5];
+/// ```
+///
+/// [`Matrix`]: crate::Matrix
+#[macro_export]
+macro_rules! dmatrix {
+    () => {
+        $crate::Matrix::from_row_major(0, 0, ::std::vec::Vec::new())
+    };
+    ($($($x:expr),+ $(,)?);+ $(;)?) => {
+        $crate::Matrix::from([$([$($x),+]),+])
+    };
+}
+
+#[cfg(test)]
+mod test_macros {
+    use crate::{Matrix, SMatrix};
+
+    #[test]
+    fn matrix() {
+        let a = matrix![0, 1, 2; 3, 4, 5];
+        let b: SMatrix<i32, 3, 1> = matrix![0; 1; 2;];
+
+        assert_eq!(a, SMatrix::from([[0, 1, 2], [3, 4, 5]]));
+        assert_eq!(b, SMatrix::from([[0], [1], [2]]));
+        assert_eq!(matrix![1 + 1, 2 * 2,], SMatrix::from([[2, 4]]));
+
+        let empty: SMatrix<f32, 0, 0> = matrix![];
+        assert_eq!(empty.shape(), (0, 0));
+    }
+
+    #[test]
+    fn dmatrix() {
+        let a = dmatrix![0., 1.; 2., 3.; 4., 5.];
+        let b = dmatrix![String::from("a"), String::from("b")];
+
+        assert_eq!(a, Matrix::from([[0., 1.], [2., 3.], [4., 5.]]));
+        assert_eq!(b[0][1], "b");
+
+        let empty: Matrix<f32> = dmatrix![];
+        assert_eq!(empty.shape(), (0, 0));
+    }
+}
@@ -0,0 +1,718 @@
+use super::exact::assert_square;
+use super::{Matrix, MatrixError, Storage};
+use num_traits::{One, Zero};
+use std::fmt::{self, Write as _};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::mem;
+
+/// How the entries of a Matrix Market file are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MarketFormat {
+    /// Only the nonzero entries, each with its row and column.
+    Coordinate,
+    /// Every entry, column after column.
+    Array,
+}
+
+/// The kind of values of a Matrix Market file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MarketField {
+    Real,
+    Integer,
+    Complex,
+    /// No values: the listed entries are ones. Only in coordinate files.
+    Pattern,
+}
+
+impl fmt::Display for MarketFormat {
+    /// Writes the name of the format in the header of a file.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Coordinate => "coordinate",
+            Self::Array => "array",
+        })
+    }
+}
+
+impl fmt::Display for MarketField {
+    /// Writes the name of the field in the header of a file.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Real => "real",
+            Self::Integer => "integer",
+            Self::Complex => "complex",
+            Self::Pattern => "pattern",
+        })
+    }
+}
+
+/// The symmetry of the matrix of a Matrix Market file, which determines the
+/// entries it lists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// All entries are listed.
+    General,
+    /// Only the lower triangle is listed; `a[(j, i)] == a[(i, j)]`.
+    Symmetric,
+    /// Only the strictly lower triangle is listed; `a[(j, i)] == -a[(i, j)]`
+    /// and the diagonal is zero.
+    SkewSymmetric,
+    /// Only the lower triangle is listed; `a[(j, i)]` is the complex
+    /// conjugate of `a[(i, j)]`.
+    Hermitian,
+}
+
+impl fmt::Display for Symmetry {
+    /// Writes the name of the symmetry in the header of a file.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::General => "general",
+            Self::Symmetric => "symmetric",
+            Self::SkewSymmetric => "skew-symmetric",
+            Self::Hermitian => "hermitian",
+        })
+    }
+}
+
+/// A type of element of the matrices read from and written to Matrix
+/// Market files.
+///
+/// It is implemented for the primitive integers and floats, and, with the
+/// `complex` feature, for [`Complex<f32>`] and [`Complex<f64>`].
+///
+/// [`Complex<f32>`]: num_complex::Complex
+/// [`Complex<f64>`]: num_complex::Complex
+pub trait MarketElement: Clone + Zero + One {
+    /// The field written in the header of the files of this type. Integer
+    /// files can also be read into real and complex types, and real files
+    /// into complex types.
+    const FIELD: MarketField;
+
+    /// Parses the value of an entry from its tokens: two for complex files,
+    /// one otherwise.
+    fn parse(tokens: &[&str]) -> Option<Self>;
+
+    /// Appends the tokens of the value to `out`, separated by spaces.
+    fn write(&self, out: &mut String);
+
+    /// Returns the opposite of the value, if it is representable.
+    fn checked_neg(&self) -> Option<Self>;
+
+    /// Returns the sum of the values, if it is representable.
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+
+    /// Returns the complex conjugate of the value.
+    fn conj(&self) -> Self;
+}
+
+macro_rules! impl_market_element {
+    (
+        $field:ident, $format:literal, |$x:ident| $neg:expr, |$a:ident, $b:ident| $add:expr;
+        $($t:ty)*
+    ) => {$(
+        impl MarketElement for $t {
+            const FIELD: MarketField = MarketField::$field;
+
+            fn parse(tokens: &[&str]) -> Option<Self> {
+                match tokens {
+                    [token] => token.parse().ok(),
+                    _ => None,
+                }
+            }
+
+            fn write(&self, out: &mut String) {
+                write!(out, $format, self).unwrap();
+            }
+
+            fn checked_neg(&self) -> Option<Self> {
+                let $x = *self;
+                $neg
+            }
+
+            fn checked_add(&self, other: &Self) -> Option<Self> {
+                let ($a, $b) = (*self, *other);
+                $add
+            }
+
+            fn conj(&self) -> Self {
+                *self
+            }
+        }
+    )*};
+}
+
+impl_market_element!(
+    Integer, "{}", |x| x.checked_neg(), |x, y| x.checked_add(y);
+    i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize
+);
+// Debug, unlike Display, switches to scientific notation for very large and
+// very small values.
+impl_market_element!(Real, "{:?}", |x| Some(-x), |x, y| Some(x + y); f32 f64);
+
+#[cfg(feature = "complex")]
+impl<T> MarketElement for num_complex::Complex<T>
+where
+    T: MarketElement + num_traits::Num + std::ops::Neg<Output = T>,
+{
+    const FIELD: MarketField = MarketField::Complex;
+
+    fn parse(tokens: &[&str]) -> Option<Self> {
+        match tokens {
+            [re] => Some(Self::new(T::parse(&[re])?, T::zero())),
+            [re, im] => Some(Self::new(T::parse(&[re])?, T::parse(&[im])?)),
+            _ => None,
+        }
+    }
+
+    fn write(&self, out: &mut String) {
+        self.re.write(out);
+        out.push(' ');
+        self.im.write(out);
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Some(-self.clone())
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self.clone() + other.clone())
+    }
+
+    fn conj(&self) -> Self {
+        num_complex::Complex::conj(self)
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: MarketElement,
+{
+    /// Reads a matrix from a Matrix Market file, in either format and with
+    /// any field and symmetry.
+    ///
+    /// The entries missing from a coordinate file are zero, and the ones
+    /// listed more than once are summed. Pattern entries are one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Parse`] if the file is malformed, if its
+    /// values cannot be represented by `T` (such as a real file read into
+    /// integers, or a skew-symmetric one into unsigned integers), if the
+    /// sum of duplicate entries overflows, if the matrix is too large or if
+    /// an entry is out of bounds, and [`MatrixError::Io`] if reading fails.
+    /// The columns of the errors count tokens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matrix::Matrix;
+    ///
+    /// let mtx = "%%MatrixMarket matrix coordinate integer symmetric
+    /// % a comment
+    /// 2 2 2
+    /// 1 1 4
+    /// 2 1 -1
+    /// ";
+    /// let a: Matrix<i32> = Matrix::read_matrix_market(mtx.as_bytes())?;
+    ///
+    /// assert_eq!(a, Matrix::from([[4, -1], [-1, 0]]));
+    /// # Ok::<(), matrix::MatrixError>(())
+    /// ```
+    pub fn read_matrix_market<R: Read>(reader: R) -> Result<Self, MatrixError> {
+        let mut lines = Lines {
+            reader: BufReader::new(reader),
+            line: 0,
+            buffer: String::new(),
+        };
+
+        let header = match lines.read_line()? {
+            true => lines.buffer.to_ascii_lowercase(),
+            false => String::new(),
+        };
+        let (format, field, symmetry) = parse_header(&header)?;
+        if !compatible(field, T::FIELD) {
+            return Err(error(
+                1,
+                4,
+                format!("cannot read {field} values into {} ones", T::FIELD),
+            ));
+        }
+        let num_values = match field {
+            MarketField::Complex => 2,
+            MarketField::Pattern => 0,
+            _ => 1,
+        };
+
+        let next_line = lines.line + 1;
+        let (line, size) = lines
+            .next_tokens()?
+            .ok_or_else(|| error(next_line, 1, "missing size line"))?;
+        let size = parse_numbers(line, &size)?;
+        let (num_rows, num_columns, num_entries) = match (format, &size[..]) {
+            (MarketFormat::Coordinate, &[m, n, nnz]) => (m, n, nnz),
+            (MarketFormat::Array, &[m, n]) => (m, n, 0),
+            _ => {
+                return Err(error(
+                    line,
+                    1,
+                    format!("unexpected size line for the {format} format"),
+                ))
+            }
+        };
+        if symmetry != Symmetry::General && num_rows != num_columns {
+            return Err(error(
+                line,
+                2,
+                format!("a {symmetry} matrix should be square"),
+            ));
+        }
+
+        let size_line = line;
+        let too_large = || error(size_line, 1, "the matrix is too large");
+        let len = num_rows
+            .checked_mul(num_columns)
+            .filter(|len| len.checked_mul(mem::size_of::<T>()) <= Some(isize::MAX as usize))
+            .ok_or_else(too_large)?;
+
+        // The entries are read before the matrix is allocated, so that a
+        // malformed file fails early; a matrix that cannot be allocated is
+        // reported as too large.
+        let mut parsed = Vec::new();
+        let mut positions = entries(num_rows, num_columns, symmetry);
+        let expected = match (format, symmetry) {
+            (MarketFormat::Coordinate, _) => num_entries,
+            (MarketFormat::Array, Symmetry::General) => len,
+            // `n * (n - 1) / 2` and `n * (n + 1) / 2`, without overflow.
+            (MarketFormat::Array, Symmetry::SkewSymmetric) => len / 2 - num_rows / 2,
+            (MarketFormat::Array, _) => len / 2 + num_rows.div_ceil(2),
+        };
+        let mut count = 0;
+        while let Some((line, tokens)) = lines.next_tokens()? {
+            if count == expected {
+                return Err(error(
+                    line,
+                    1,
+                    format!("expected {expected} entries, found more"),
+                ));
+            }
+            let (i, j, values) = match format {
+                MarketFormat::Coordinate => {
+                    let ij = parse_numbers(line, tokens.get(..2).unwrap_or(&tokens))?;
+                    match ij[..] {
+                        [i, j] if (1..=num_rows).contains(&i) && (1..=num_columns).contains(&j) => {
+                            (i - 1, j - 1, &tokens[2..])
+                        }
+                        [_, _] => return Err(error(line, 1, "the entry is out of bounds")),
+                        _ => return Err(error(line, 1, "missing row or column")),
+                    }
+                }
+                MarketFormat::Array => {
+                    let (i, j) = positions.next().expect("the count of entries is checked");
+                    (i, j, &tokens[..])
+                }
+            };
+            let column = tokens.len() - values.len() + 1;
+            if values.len() != num_values {
+                let message = format!("expected {num_values} values, found {}", values.len());
+                return Err(error(line, column, message));
+            }
+            let value = match field {
+                MarketField::Pattern => Some(T::one()),
+                _ => T::parse(values),
+            }
+            .ok_or_else(|| error(line, column, format!("cannot parse `{}`", values.join(" "))))?;
+            parsed.push((line, column, i, j, value));
+            count += 1;
+        }
+        if count < expected {
+            let message = format!("expected {expected} entries, found {count}");
+            return Err(error(lines.line + 1, 1, message));
+        }
+
+        let mut data = Vec::new();
+        data.try_reserve_exact(len).map_err(|_| too_large())?;
+        data.resize(len, T::zero());
+        let mut a = Matrix::from_row_major(num_rows, num_columns, data);
+        for (line, column, i, j, value) in parsed {
+            let add = |sum: &mut T, value: T| {
+                *sum = sum.checked_add(&value).ok_or_else(|| {
+                    error(line, column, "the sum of the values cannot be represented")
+                })?;
+                Ok::<_, MatrixError>(())
+            };
+            if symmetry != Symmetry::General && i != j {
+                let mirrored = match symmetry {
+                    Symmetry::SkewSymmetric => value.checked_neg().ok_or_else(|| {
+                        error(
+                            line,
+                            column,
+                            "the opposite of the value cannot be represented",
+                        )
+                    })?,
+                    Symmetry::Hermitian => value.conj(),
+                    _ => value.clone(),
+                };
+                add(&mut a[(j, i)], mirrored)?;
+            }
+            add(&mut a[(i, j)], value)?;
+        }
+        Ok(a)
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    T: MarketElement,
+    S: Storage<T>,
+{
+    /// Writes the matrix to a Matrix Market file, with the field of `T`.
+    ///
+    /// Coordinate files list the nonzero entries only. Both formats list
+    /// the entries column after column, and only the ones of the lower
+    /// triangle unless `symmetry` is [`Symmetry::General`]; the upper
+    /// triangle is then assumed to match, and is not checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Io`] if writing fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `symmetry` is not [`Symmetry::General`] and the matrix is
+    /// not square.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matrix::{MarketFormat, Matrix, Symmetry};
+    ///
+    /// let a: Matrix<f64> = Matrix::from([[1., 0.], [0.5, 2.]]);
+    /// let mut mtx = Vec::new();
+    /// a.write_matrix_market(&mut mtx, MarketFormat::Coordinate, Symmetry::General)?;
+    ///
+    /// let expected = "%%MatrixMarket matrix coordinate real general\n2 2 3\n1 1 1.0\n2 1 0.5\n2 2 2.0\n";
+    /// assert_eq!(String::from_utf8(mtx).unwrap(), expected);
+    /// # Ok::<(), matrix::MatrixError>(())
+    /// ```
+    pub fn write_matrix_market<W: Write>(
+        &self,
+        writer: W,
+        format: MarketFormat,
+        symmetry: Symmetry,
+    ) -> Result<(), MatrixError> {
+        let (num_rows, num_columns) = self.shape();
+        if symmetry != Symmetry::General {
+            assert_square(num_rows, num_columns);
+        }
+
+        let mut writer = BufWriter::new(writer);
+        let field = T::FIELD;
+        writeln!(writer, "%%MatrixMarket matrix {format} {field} {symmetry}")?;
+
+        let entries = entries(num_rows, num_columns, symmetry);
+        let mut out = String::new();
+        match format {
+            MarketFormat::Coordinate => {
+                let nonzero = || entries.clone().filter(|&(i, j)| !self[(i, j)].is_zero());
+                writeln!(writer, "{num_rows} {num_columns} {}", nonzero().count())?;
+                for (i, j) in nonzero() {
+                    out.clear();
+                    self[(i, j)].write(&mut out);
+                    writeln!(writer, "{} {} {out}", i + 1, j + 1)?;
+                }
+            }
+            MarketFormat::Array => {
+                writeln!(writer, "{num_rows} {num_columns}")?;
+                for (i, j) in entries {
+                    out.clear();
+                    self[(i, j)].write(&mut out);
+                    writeln!(writer, "{out}")?;
+                }
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Returns the positions of the entries of a matrix of the given shape and
+/// symmetry listed in a file, column after column.
+fn entries(
+    num_rows: usize,
+    num_columns: usize,
+    symmetry: Symmetry,
+) -> impl Iterator<Item = (usize, usize)> + Clone {
+    let first_row = move |j| match symmetry {
+        Symmetry::General => 0,
+        Symmetry::SkewSymmetric => j + 1,
+        _ => j,
+    };
+    (0..num_columns).flat_map(move |j| (first_row(j)..num_rows).map(move |i| (i, j)))
+}
+
+/// Parses the banner line of a Matrix Market file, already in lowercase.
+fn parse_header(header: &str) -> Result<(MarketFormat, MarketField, Symmetry), MatrixError> {
+    let tokens: Vec<&str> = header.split_whitespace().collect();
+    match tokens.get(..2) {
+        Some(["%%matrixmarket", "matrix"]) => {}
+        _ => return Err(error(1, 1, "expected `%%MatrixMarket matrix`")),
+    }
+    let format = match tokens.get(2) {
+        Some(&"coordinate") => MarketFormat::Coordinate,
+        Some(&"array") => MarketFormat::Array,
+        _ => return Err(error(1, 3, "expected `coordinate` or `array`")),
+    };
+    let field = match tokens.get(3) {
+        Some(&"real") => MarketField::Real,
+        Some(&"integer") => MarketField::Integer,
+        Some(&"complex") => MarketField::Complex,
+        Some(&"pattern") if format == MarketFormat::Coordinate => MarketField::Pattern,
+        _ => {
+            return Err(error(
+                1,
+                4,
+                "expected `real`, `integer`, `complex` or `pattern`",
+            ))
+        }
+    };
+    let symmetry = match tokens.get(4) {
+        Some(&"general") => Symmetry::General,
+        Some(&"symmetric") => Symmetry::Symmetric,
+        Some(&"skew-symmetric") if field != MarketField::Pattern => Symmetry::SkewSymmetric,
+        Some(&"hermitian") if field != MarketField::Pattern => Symmetry::Hermitian,
+        _ => {
+            let message = "expected `general`, `symmetric`, `skew-symmetric` or `hermitian`";
+            return Err(error(1, 5, message));
+        }
+    };
+    Ok((format, field, symmetry))
+}
+
+/// Returns whether the values of a file with the given field can be read
+/// into a type with field `into`.
+fn compatible(field: MarketField, into: MarketField) -> bool {
+    use MarketField::*;
+    matches!(
+        (field, into),
+        (Pattern | Integer, _) | (Real, Real | Complex) | (Complex, Complex)
+    )
+}
+
+/// Parses `tokens`, of the given line, as nonnegative integers.
+fn parse_numbers(line: usize, tokens: &[&str]) -> Result<Vec<usize>, MatrixError> {
+    let parse = |(k, token): (usize, &&str)| {
+        let message = || format!("cannot parse `{token}` as a nonnegative integer");
+        token.parse().map_err(|_| error(line, k + 1, message()))
+    };
+    tokens.iter().enumerate().map(parse).collect()
+}
+
+fn error(line: usize, column: usize, message: impl Into<String>) -> MatrixError {
+    MatrixError::Parse {
+        line,
+        column,
+        message: message.into(),
+    }
+}
+
+/// Reads the lines of a Matrix Market file.
+struct Lines<R> {
+    reader: R,
+    line: usize,
+    buffer: String,
+}
+
+impl<R: BufRead> Lines<R> {
+    /// Returns the number and tokens of the next line that is neither blank
+    /// nor a comment, or `None` at the end of the input.
+    fn next_tokens(&mut self) -> Result<Option<(usize, Vec<&str>)>, MatrixError> {
+        loop {
+            if !self.read_line()? {
+                return Ok(None);
+            }
+            let text = self.buffer.trim();
+            if !text.is_empty() && !text.starts_with('%') {
+                break;
+            }
+        }
+        Ok(Some((self.line, self.buffer.split_whitespace().collect())))
+    }
+
+    /// Reads the next line into the buffer, or returns `false` at the end
+    /// of the input.
+    fn read_line(&mut self) -> Result<bool, MatrixError> {
+        self.buffer.clear();
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            return Ok(false);
+        }
+        self.line += 1;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test_market {
+    use crate::{MarketElement, MarketFormat, Matrix, MatrixError, Symmetry};
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+        std::fs::read(path).unwrap()
+    }
+
+    fn round_trip<T>(a: &Matrix<T>, format: MarketFormat, symmetry: Symmetry) -> Matrix<T>
+    where
+        T: MarketElement + std::fmt::Debug + PartialEq,
+    {
+        let mut mtx = Vec::new();
+        a.write_matrix_market(&mut mtx, format, symmetry).unwrap();
+        Matrix::read_matrix_market(&mtx[..]).unwrap()
+    }
+
+    fn parse_error<T: MarketElement + std::fmt::Debug>(mtx: &str) -> (usize, usize, String) {
+        match Matrix::<T>::read_matrix_market(mtx.as_bytes()) {
+            Err(MatrixError::Parse {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            result => panic!("expected a parse error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn general() {
+        let a: Matrix<f64> = Matrix::read_matrix_market(&fixture("general.mtx")[..]).unwrap();
+        let expected = Matrix::from([
+            [1., 0., 0., 6.],
+            [0., 10.5, 0., 0.],
+            [0., 0., 0.015, 0.],
+            [0., 250.5, 0., -280.],
+            [0., 0., 0., 0.],
+        ]);
+
+        assert_eq!(a, expected);
+        for format in [MarketFormat::Coordinate, MarketFormat::Array] {
+            assert_eq!(round_trip(&a, format, Symmetry::General), a);
+        }
+    }
+
+    #[test]
+    fn symmetric() {
+        let a: Matrix<f32> = Matrix::read_matrix_market(&fixture("symmetric.mtx")[..]).unwrap();
+
+        assert_eq!(
+            a,
+            Matrix::from([[2., -1., 0.], [-1., 2., -1.], [0., -1., 2.]])
+        );
+        assert_eq!(
+            round_trip(&a, MarketFormat::Coordinate, Symmetry::Symmetric),
+            a
+        );
+        assert_eq!(round_trip(&a, MarketFormat::Array, Symmetry::Symmetric), a);
+    }
+
+    #[test]
+    fn skew_symmetric() {
+        let a: Matrix<i64> = Matrix::read_matrix_market(&fixture("skew.mtx")[..]).unwrap();
+
+        assert_eq!(a, Matrix::from([[0, -1, -2], [1, 0, -3], [2, 3, 0]]));
+        assert_eq!(
+            round_trip(&a, MarketFormat::Array, Symmetry::SkewSymmetric),
+            a
+        );
+        assert_eq!(
+            round_trip(&a, MarketFormat::Coordinate, Symmetry::SkewSymmetric),
+            a
+        );
+
+        let (line, column, _) =
+            parse_error::<u8>(std::str::from_utf8(&fixture("skew.mtx")).unwrap());
+        assert_eq!((line, column), (4, 1));
+    }
+
+    #[test]
+    fn pattern() {
+        let a: Matrix<u8> = Matrix::read_matrix_market(&fixture("pattern.mtx")[..]).unwrap();
+
+        assert_eq!(a, Matrix::from([[1, 1, 0], [1, 0, 0], [0, 0, 1]]));
+    }
+
+    #[cfg(feature = "complex")]
+    #[test]
+    fn hermitian() {
+        use crate::Complex;
+
+        let a: Matrix<Complex<f64>> =
+            Matrix::read_matrix_market(&fixture("hermitian.mtx")[..]).unwrap();
+        let c = Complex::new;
+
+        assert_eq!(
+            a,
+            Matrix::from([[c(2., 0.), c(1., -1.)], [c(1., 1.), c(3., 0.)]])
+        );
+        assert_eq!(
+            round_trip(&a, MarketFormat::Coordinate, Symmetry::Hermitian),
+            a
+        );
+        assert_eq!(round_trip(&a, MarketFormat::Array, Symmetry::General), a);
+    }
+
+    #[test]
+    fn errors() {
+        let header = "%%MatrixMarket matrix coordinate real general\n";
+
+        assert_eq!(parse_error::<f64>("%%MatrixMarket tensor").0, 1);
+        assert_eq!(
+            parse_error::<i32>(header),
+            (
+                1,
+                4,
+                "cannot read real values into integer ones".to_string()
+            )
+        );
+        assert_eq!(
+            parse_error::<f64>(&format!("{header}2 2 1\n3 1 1.0\n")),
+            (3, 1, "the entry is out of bounds".to_string())
+        );
+        assert_eq!(
+            parse_error::<f64>(&format!("{header}%\n2 2 2\n1 1 x\n")),
+            (4, 3, "cannot parse `x`".to_string())
+        );
+        assert_eq!(
+            parse_error::<f64>(&format!("{header}2 2 2\n1 1 1.0\n")),
+            (4, 1, "expected 2 entries, found 1".to_string())
+        );
+        assert_eq!(
+            parse_error::<f64>("%%MatrixMarket matrix array real symmetric\n2 3\n"),
+            (2, 2, "a symmetric matrix should be square".to_string())
+        );
+        assert_eq!(
+            parse_error::<f64>(&format!("{header}4294967296 4294967296 0\n")),
+            (2, 1, "the matrix is too large".to_string())
+        );
+        // Small enough for `isize`, too large for any allocator.
+        assert_eq!(
+            parse_error::<f64>(&format!("{header}1073741824 536870912 1\n1 1 1.0\n")),
+            (2, 1, "the matrix is too large".to_string())
+        );
+    }
+
+    #[test]
+    fn duplicates() {
+        let header = "%%MatrixMarket matrix coordinate integer general\n";
+        let mtx = format!("{header}1 1 3\n1 1 2\n1 1 100\n1 1 100\n");
+
+        assert_eq!(
+            Matrix::<i32>::read_matrix_market(mtx.as_bytes()).unwrap(),
+            Matrix::from([[202]])
+        );
+        assert_eq!(
+            parse_error::<i8>(&mtx),
+            (
+                5,
+                3,
+                "the sum of the values cannot be represented".to_string()
+            )
+        );
+    }
+}
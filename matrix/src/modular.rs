@@ -0,0 +1,326 @@
+use super::exact::Field;
+use super::Matrix;
+use num_traits::{One, Zero};
+use rayon::prelude::*;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// An element of the prime field GF(`P`), written as `ModP<P>`.
+///
+/// The value is always kept reduced, that is, in the range `0..P`. The
+/// modulus `P` must be a prime number in the range `2..=2^63`; primality is
+/// not checked, but division by an element without an inverse panics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ModP<const P: u64>(u64);
+
+impl<const P: u64> ModP<P> {
+    const VALID_MODULUS: () = assert!(P >= 2 && P <= 1 << 63, "`P` should be in `2..=2^63`");
+
+    /// Creates a `ModP<P>` holding `value` reduced modulo `P`.
+    #[inline]
+    pub const fn new(value: u64) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_MODULUS;
+        Self(value % P)
+    }
+
+    /// Creates a `ModP<P>` holding `value` reduced modulo `P`, mapping
+    /// negative values to their additive inverses.
+    #[inline]
+    pub const fn from_i64(value: i64) -> Self {
+        let reduced = Self::new(value.unsigned_abs());
+        if value < 0 {
+            Self::new(P - reduced.0)
+        } else {
+            reduced
+        }
+    }
+
+    /// Returns the modulus `P`.
+    #[inline]
+    pub const fn modulus() -> u64 {
+        P
+    }
+
+    /// Returns the reduced value, in the range `0..P`.
+    #[inline]
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Raises `self` to the power `exponent`, using exponentiation by
+    /// squaring.
+    pub fn pow(self, mut exponent: u64) -> Self {
+        let mut base = self;
+        let mut result = Self::one();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// Returns the multiplicative inverse of `self`, or `None` if `self`
+    /// is zero.
+    #[inline]
+    pub fn inverse(self) -> Option<Self> {
+        if self.0 == 0 {
+            None
+        } else {
+            // Fermat's little theorem: a^(P - 2) * a = a^(P - 1) = 1.
+            Some(self.pow(P - 2))
+        }
+    }
+}
+
+impl<const P: u64> From<u64> for ModP<P> {
+    #[inline]
+    fn from(value: u64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<const P: u64> fmt::Display for ModP<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<const P: u64> Add for ModP<P> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        // Both operands are below `P <= 2^63`, so the sum cannot overflow.
+        let sum = self.0 + rhs.0;
+        Self(if sum >= P { sum - P } else { sum })
+    }
+}
+
+impl<const P: u64> Sub for ModP<P> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        if self.0 >= rhs.0 {
+            Self(self.0 - rhs.0)
+        } else {
+            Self(self.0 + (P - rhs.0))
+        }
+    }
+}
+
+impl<const P: u64> Mul for ModP<P> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 * rhs.0 as u128) % P as u128) as u64)
+    }
+}
+
+impl<const P: u64> Div for ModP<P> {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        match rhs.inverse() {
+            Some(inverse) => self * inverse,
+            None => panic!("attempt to divide by zero in GF({P})"),
+        }
+    }
+}
+
+impl<const P: u64> Neg for ModP<P> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+
+impl<const P: u64> AddAssign for ModP<P> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const P: u64> SubAssign for ModP<P> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const P: u64> MulAssign for ModP<P> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const P: u64> DivAssign for ModP<P> {
+    #[inline]
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const P: u64> Zero for ModP<P> {
+    #[inline]
+    fn zero() -> Self {
+        Self::new(0)
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const P: u64> One for ModP<P> {
+    #[inline]
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<const P: u64> Field for ModP<P> {}
+
+impl<const P: u64> Matrix<ModP<P>> {
+    /// Multiplies matrix `a` by matrix `b` over GF(`P`), producing
+    /// `c = a * b`.
+    ///
+    /// Unlike [`Matrix::mul`], which reduces after every product, this
+    /// method accumulates unreduced products in 128-bit integers and only
+    /// reduces them when the accumulator could overflow. For `P < 2^32` this
+    /// means a single reduction per element of `c`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a.num_columns() != b.num_rows()`.
+    pub fn mul_mod(a: &Self, b: &Self) -> Self {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(a_num_columns: usize, b_num_rows: usize) -> ! {
+            panic!(
+                "`a.num_columns()` (is {a_num_columns}) \
+                should be equal to `b.num_rows()` (is {b_num_rows})"
+            );
+        }
+
+        if a.num_columns() != b.num_rows() {
+            assert_failed(a.num_columns(), b.num_rows());
+        }
+
+        let (a, b) = (a.as_row_major(), b.as_row_major());
+
+        // Number of products of two reduced values that fit in an
+        // accumulator which already holds a reduced value.
+        let max_product = (P as u128 - 1) * (P as u128 - 1);
+        let lazy_limit = (u128::MAX - P as u128)
+            .checked_div(max_product)
+            .map_or(usize::MAX, |limit| limit.min(usize::MAX as u128) as usize);
+
+        let mut c: Matrix<ModP<P>> = Matrix::zeros(a.num_rows(), b.num_columns());
+
+        c.rows_mut()
+            .zip(a.rows())
+            .par_bridge()
+            .for_each(|(ci, ai)| {
+                let mut accumulators = vec![0u128; ci.len()];
+                let mut pending = 0;
+                b.rows().zip(ai.iter()).for_each(|(bk, aik)| {
+                    if pending == lazy_limit {
+                        accumulators.iter_mut().for_each(|acc| *acc %= P as u128);
+                        pending = 0;
+                    }
+                    let aik = aik.0 as u128;
+                    accumulators
+                        .iter_mut()
+                        .zip(bk.iter())
+                        .for_each(|(acc, bkj)| {
+                            (*acc) += aik * bkj.0 as u128;
+                        });
+                    pending += 1;
+                });
+                ci.iter_mut()
+                    .zip(accumulators.iter())
+                    .for_each(|(cij, acc)| {
+                        *cij = ModP((acc % P as u128) as u64);
+                    });
+            });
+        c
+    }
+}
+
+#[cfg(test)]
+mod test_mod_p {
+    use super::ModP;
+    use crate::Matrix;
+
+    type F7 = ModP<7>;
+    type Big = ModP<{ (1 << 61) - 1 }>;
+
+    #[test]
+    fn arithmetic() {
+        let a = F7::new(5);
+        let b = F7::new(4);
+
+        assert_eq!(a + b, F7::new(2));
+        assert_eq!(a - b, F7::new(1));
+        assert_eq!(b - a, F7::new(6));
+        assert_eq!(a * b, F7::new(6));
+        assert_eq!(a / b, F7::new(3));
+        assert_eq!(-a, F7::new(2));
+        assert_eq!(F7::from_i64(-3), F7::new(4));
+    }
+
+    #[test]
+    fn inverse() {
+        for value in 1..7 {
+            let a = F7::new(value);
+            assert_eq!(a * a.inverse().unwrap(), F7::new(1));
+        }
+        assert_eq!(F7::new(0).inverse(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero in GF(7)")]
+    fn division_by_zero() {
+        let _ = F7::new(1) / F7::new(0);
+    }
+
+    #[test]
+    fn mul_mod() {
+        let a: Matrix<F7> = Matrix::from([[1, 2, 3], [4, 5, 6]].map(|row| row.map(F7::new)));
+        let b: Matrix<F7> = Matrix::from([[6, 5], [4, 3], [2, 1]].map(|row| row.map(F7::new)));
+        let c = Matrix::mul_mod(&a, &b);
+
+        assert_eq!(c, Matrix::mul(&a, &b));
+        assert_eq!(
+            c,
+            Matrix::from([[20, 14], [56, 41]].map(|row| row.map(F7::new)))
+        );
+    }
+
+    #[test]
+    fn mul_mod_with_large_modulus() {
+        let p = Big::modulus();
+        let a: Matrix<Big> = Matrix::full(3, 100, Big::new(p - 1));
+        let c = Matrix::mul_mod(&a, &Matrix::full(100, 2, Big::new(p - 2)));
+
+        // (-1) * (-2) summed 100 times, which forces intermediate reductions.
+        assert_eq!(c, Matrix::full(3, 2, Big::new(200)));
+    }
+}
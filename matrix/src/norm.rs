@@ -0,0 +1,371 @@
+use super::{Axis, Layout, Matrix, Storage};
+use num_traits::Float;
+use rayon::prelude::*;
+use std::ops::AddAssign;
+
+/// A distance between two vectors, used by [`Matrix::cdist`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// The square root of the sum of the squared differences.
+    Euclidean,
+    /// The sum of the absolute differences.
+    Manhattan,
+    /// One minus the cosine of the angle between the vectors. It is NaN if
+    /// either vector is zero.
+    Cosine,
+    /// The largest absolute difference.
+    Chebyshev,
+}
+
+/// Maximum number of sweeps of the Jacobi singular value algorithm, which
+/// usually converges in fewer than ten.
+const MAX_JACOBI_SWEEPS: usize = 64;
+
+/// Seed of the starting vector of the power iteration.
+const POWER_ITERATION_SEED: u64 = 0x5eed;
+
+impl<T, S> Matrix<T, S>
+where
+    T: Float + AddAssign + Send + Sync,
+    S: Storage<T>,
+{
+    /// Returns the Frobenius norm of the matrix, the square root of the sum
+    /// of the squares of its elements.
+    pub fn norm_frobenius(&self) -> T {
+        self.par_fold(T::zero(), |sum, &x| sum + x * x, |a, b| a + b)
+            .sqrt()
+    }
+
+    /// Returns the 1-norm of the matrix, the largest sum of the absolute
+    /// values of the elements of a column.
+    pub fn norm_1(&self) -> T {
+        let sums = self.map(|x| x.abs()).sum_axis(Axis::Columns);
+        sums.into_iter().fold(T::zero(), T::max)
+    }
+
+    /// Returns the ∞-norm of the matrix, the largest sum of the absolute
+    /// values of the elements of a row.
+    pub fn norm_inf(&self) -> T {
+        let sums = self.map(|x| x.abs()).sum_axis(Axis::Rows);
+        sums.into_iter().fold(T::zero(), T::max)
+    }
+
+    /// Returns the largest absolute value of the elements of the matrix,
+    /// or zero if it is empty.
+    pub fn norm_max(&self) -> T {
+        self.par_fold(T::zero(), |max, &x| max.max(x.abs()), T::max)
+    }
+
+    /// Returns the nuclear norm of the matrix, the sum of its singular
+    /// values.
+    ///
+    /// The singular values are computed by the one-sided Jacobi algorithm,
+    /// which is accurate but takes `O(m * n * min(m, n))` time per sweep.
+    pub fn norm_nuclear(&self) -> T {
+        self.singular_values()
+            .into_iter()
+            .fold(T::zero(), |sum, x| sum + x)
+    }
+
+    /// Estimates the spectral norm of the matrix, its largest singular
+    /// value, by at most `max_iterations` steps of the power iteration on
+    /// `A^T * A`.
+    ///
+    /// The iteration stops early once the estimate changes by less than the
+    /// square root of the machine epsilon, relative to its value. The
+    /// estimate never exceeds the true norm, apart from rounding, and is
+    /// deterministic.
+    pub fn norm_spectral_estimate(&self, max_iterations: usize) -> T {
+        let (m, n) = self.shape();
+        if m == 0 || n == 0 {
+            return T::zero();
+        }
+
+        let a = self.view();
+        let a_t = Matrix::from_row_major(n, m, a.to_layout(Layout::ColumnMajor).into_storage());
+        let mut x = Matrix::random_normal(n, 1, T::zero(), T::one(), POWER_ITERATION_SEED);
+        let tolerance = T::epsilon().sqrt();
+        let mut estimate = T::zero();
+
+        for _ in 0..max_iterations {
+            let norm = x.norm_frobenius();
+            if norm == T::zero() {
+                break;
+            }
+            x.map_inplace(|xi| *xi = *xi / norm);
+
+            let y = Matrix::mul(&a, &x);
+            let previous = estimate;
+            estimate = y.norm_frobenius();
+            x = Matrix::mul(&a_t, &y);
+
+            if (estimate - previous).abs() <= tolerance * estimate {
+                break;
+            }
+        }
+        estimate
+    }
+
+    /// Returns the relative error of the matrix with respect to the
+    /// reference `other`, that is, `‖self - other‖ / ‖other‖` in the
+    /// Frobenius norm.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrices do not have the same shape.
+    #[track_caller]
+    pub fn relative_error<SO>(&self, other: &Matrix<T, SO>) -> T
+    where
+        SO: Storage<T>,
+    {
+        let difference = self.zip_with(other, |&x, &y| x - y);
+        difference.norm_frobenius() / other.norm_frobenius()
+    }
+
+    /// Returns the singular values of the matrix, in no particular order.
+    fn singular_values(&self) -> Vec<T> {
+        // The algorithm orthogonalizes the columns of a matrix with at least
+        // as many rows as columns, so it runs on the transpose of a wide
+        // matrix, which has the same singular values. The columns of a
+        // matrix are the rows of its transpose.
+        let (m, n) = self.shape();
+        let a = self.view();
+        let mut u: Vec<Vec<T>> = if m >= n {
+            let a = a.to_layout(Layout::ColumnMajor);
+            a.columns().map(<[T]>::to_vec).collect()
+        } else {
+            let a = a.to_layout(Layout::RowMajor);
+            a.rows().map(<[T]>::to_vec).collect()
+        };
+
+        // One-sided Jacobi: rotate pairs of columns until all of them are
+        // orthogonal. Their norms are then the singular values.
+        for _ in 0..MAX_JACOBI_SWEEPS {
+            let mut rotated = false;
+            for p in 0..u.len() {
+                for q in p + 1..u.len() {
+                    let (head, tail) = u.split_at_mut(q);
+                    let (up, uq) = (&mut head[p], &mut tail[0]);
+                    let alpha = dot(up, up);
+                    let beta = dot(uq, uq);
+                    let gamma = dot(up, uq);
+                    if gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+
+                    let zeta = (beta - alpha) / (gamma + gamma);
+                    let t = zeta.signum() / (zeta.abs() + (T::one() + zeta * zeta).sqrt());
+                    let c = T::one() / (T::one() + t * t).sqrt();
+                    let s = c * t;
+                    up.iter_mut().zip(uq.iter_mut()).for_each(|(x, y)| {
+                        let (xp, yq) = (*x, *y);
+                        *x = c * xp - s * yq;
+                        *y = s * xp + c * yq;
+                    });
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+
+        u.iter().map(|column| dot(column, column).sqrt()).collect()
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Float + AddAssign + Send + Sync,
+{
+    /// Returns the matrix of the distances between each row of `a` and
+    /// each row of `b`, measured by `metric`: its element `(i, j)` is the
+    /// distance between row `i` of `a` and row `j` of `b`.
+    ///
+    /// Euclidean distances are computed as `‖a_i‖² + ‖b_j‖² - 2 a_i · b_j`,
+    /// with the dot products given by [`Matrix::mul`]. This is much faster
+    /// than computing the differences, but small distances between large
+    /// vectors lose relative accuracy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a.num_columns() != b.num_columns()`.
+    #[track_caller]
+    pub fn cdist<SA, SB>(a: &Matrix<T, SA>, b: &Matrix<T, SB>, metric: Metric) -> Self
+    where
+        SA: Storage<T>,
+        SB: Storage<T>,
+    {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(a_num_columns: usize, b_num_columns: usize) -> ! {
+            panic!(
+                "`a.num_columns()` (is {a_num_columns}) \
+                should be equal to `b.num_columns()` (is {b_num_columns})"
+            );
+        }
+
+        if a.num_columns() != b.num_columns() {
+            assert_failed(a.num_columns(), b.num_columns());
+        }
+
+        if metric == Metric::Euclidean {
+            return euclidean(a, b);
+        }
+
+        let a = a.view().to_layout(Layout::RowMajor);
+        let b = b.view().to_layout(Layout::RowMajor);
+        let distance = |x: &[T], y: &[T]| -> T {
+            let pairs = x.iter().zip(y);
+            match metric {
+                Metric::Manhattan => pairs.fold(T::zero(), |sum, (&p, &q)| sum + (p - q).abs()),
+                Metric::Chebyshev => pairs.fold(T::zero(), |max, (&p, &q)| max.max((p - q).abs())),
+                Metric::Cosine => T::one() - dot(x, y) / (dot(x, x).sqrt() * dot(y, y).sqrt()),
+                Metric::Euclidean => unreachable!(),
+            }
+        };
+
+        let mut d = Self::zeros(a.num_rows(), b.num_rows());
+        let rows: Vec<&mut [T]> = d.rows_mut().collect();
+        rows.into_par_iter()
+            .zip(a.rows().collect::<Vec<_>>())
+            .for_each(|(di, ai)| {
+                di.iter_mut()
+                    .zip(b.rows())
+                    .for_each(|(dij, bj)| *dij = distance(ai, bj));
+            });
+        d
+    }
+}
+
+/// Returns the Euclidean distances between the rows of `a` and the rows of
+/// `b`, through their Gram matrix.
+fn euclidean<T, SA, SB>(a: &Matrix<T, SA>, b: &Matrix<T, SB>) -> Matrix<T>
+where
+    T: Float + AddAssign + Send + Sync,
+    SA: Storage<T>,
+    SB: Storage<T>,
+{
+    let (n, k) = b.shape();
+    let b_t = Matrix::from_column_major(k, n, b.view().to_layout(Layout::RowMajor).into_storage());
+    let a_norms = a.map(|&x| x * x).sum_axis(Axis::Rows);
+    let b_norms = b.map(|&x| x * x).sum_axis(Axis::Rows);
+    let two = T::one() + T::one();
+
+    let mut d = Matrix::mul(a, &b_t);
+    d.par_apply_indexed(|i, j, gij| {
+        // Rounding can make the squared distance slightly negative.
+        let squared = a_norms[i] + b_norms[j] - two * *gij;
+        *gij = squared.max(T::zero()).sqrt();
+    });
+    d
+}
+
+fn dot<T: Float>(x: &[T], y: &[T]) -> T {
+    x.iter().zip(y).fold(T::zero(), |sum, (&p, &q)| sum + p * q)
+}
+
+#[cfg(test)]
+mod test_norm {
+    use crate::{Layout, Matrix, Metric};
+
+    #[test]
+    fn entrywise_and_induced_norms() {
+        let a: Matrix<f64> = Matrix::from([[1., -2.], [-3., 4.]]);
+
+        assert_eq!(a.norm_frobenius(), 30f64.sqrt());
+        assert_eq!(a.norm_1(), 6.);
+        assert_eq!(a.norm_inf(), 7.);
+        assert_eq!(a.norm_max(), 4.);
+        assert_eq!(a.to_layout(Layout::ColumnMajor).norm_1(), 6.);
+        assert_eq!(Matrix::<f32>::zeros(0, 3).norm_inf(), 0.);
+    }
+
+    #[test]
+    fn nuclear_norm() {
+        let a: Matrix<f64> = Matrix::from([[3., 0.], [0., -4.]]);
+        assert!((a.norm_nuclear() - 7.).abs() < 1e-12);
+
+        // The outer product of `u` and `v` has a single singular value,
+        // `‖u‖ * ‖v‖`.
+        let u: Matrix<f64> = Matrix::from([[1.], [2.], [2.]]);
+        let v: Matrix<f64> = Matrix::from([[3., 4.]]);
+        let uv = Matrix::mul(&u, &v);
+        assert!((uv.norm_nuclear() - 15.).abs() < 1e-12);
+        assert!((v.norm_nuclear() - 5.).abs() < 1e-12);
+
+        let q: Matrix<f64> = Matrix::random_orthogonal(8, 0);
+        assert!((q.norm_nuclear() - 8.).abs() < 1e-10);
+    }
+
+    #[test]
+    fn spectral_norm() {
+        let a: Matrix<f64> = Matrix::from([[2., 0., 0.], [0., -5., 0.]]);
+        assert!((a.norm_spectral_estimate(100) - 5.).abs() < 1e-6);
+
+        let q: Matrix<f64> = Matrix::random_orthogonal(10, 1);
+        assert!((q.norm_spectral_estimate(100) - 1.).abs() < 1e-6);
+        assert_eq!(Matrix::<f64>::zeros(0, 2).norm_spectral_estimate(10), 0.);
+    }
+
+    #[test]
+    fn relative_error() {
+        let a: Matrix<f64> = Matrix::from([[3., 4.]]);
+        let b = Matrix::from([[3., 4.5]]);
+
+        assert_eq!(b.relative_error(&a), 0.1);
+        assert_eq!(a.relative_error(&a), 0.);
+    }
+
+    #[test]
+    fn cdist() {
+        let a: Matrix<f64> = Matrix::from([[0., 0.], [1., 1.]]);
+        let b = Matrix::from([[3., 4.], [1., 0.], [-1., -1.]]);
+
+        assert_eq!(
+            Matrix::cdist(&a, &b, Metric::Euclidean),
+            Matrix::from([[5., 1., 2f64.sqrt()], [13f64.sqrt(), 1., 8f64.sqrt()]])
+        );
+        assert_eq!(
+            Matrix::cdist(&a, &b, Metric::Manhattan),
+            Matrix::from([[7., 1., 2.], [5., 1., 4.]])
+        );
+        assert_eq!(
+            Matrix::cdist(&a, &b, Metric::Chebyshev),
+            Matrix::from([[4., 1., 1.], [3., 1., 2.]])
+        );
+
+        let cosine = Matrix::cdist(&a, &b, Metric::Cosine);
+        assert!(cosine[0].iter().all(|x| x.is_nan()));
+        assert!((cosine[(1, 2)] - 2.).abs() < 1e-12);
+        assert!((cosine[(1, 1)] - (1. - 0.5f64.sqrt())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn cdist_euclidean_matches_direct_computation() {
+        let a: Matrix<f64> = Matrix::random_uniform(20, 7, -1., 1., 0);
+        let b = Matrix::random_uniform(15, 7, -1., 1., 1).into_layout(Layout::ColumnMajor);
+        let d = Matrix::cdist(&a, &b, Metric::Euclidean);
+
+        d.for_each_indexed(|i, j, &dij| {
+            let direct = (0..7)
+                .map(|k| (a[(i, k)] - b[(j, k)]).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            assert!((dij - direct).abs() < 1e-12);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`a.num_columns()` (is 2) should be equal to `b.num_columns()` (is 3)"
+    )]
+    fn cdist_with_different_dimensions() {
+        Matrix::<f64>::cdist(
+            &Matrix::zeros(1, 2),
+            &Matrix::zeros(1, 3),
+            Metric::Manhattan,
+        );
+    }
+}
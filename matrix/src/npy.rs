@@ -0,0 +1,622 @@
+use super::{Layout, Matrix, MatrixError, Storage};
+use std::borrow::Cow;
+use std::io::{BufWriter, Read, Write};
+use std::{any, mem, slice};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Length of the chunks in which [`Matrix::read_npy`] reads the data, a
+/// multiple of the size of every element type.
+const CHUNK_BYTES: usize = 1 << 16;
+
+/// The dtypes, without byte order, of the implementors of [`NpyElement`].
+const DTYPES: [&str; 6] = ["f4", "f8", "i4", "i8", "u1", "b1"];
+
+mod private {
+    pub trait Sealed: Sized {
+        /// The kind and size of the NumPy dtype, as in `f8`.
+        const DTYPE: &'static str;
+
+        /// Decodes an element from its bytes, in native order, or returns
+        /// `None` if they do not represent one.
+        fn from_ne_bytes(bytes: &[u8]) -> Option<Self>;
+
+        /// Appends the bytes of the element, in native order, to `out`.
+        fn extend_ne_bytes(self, out: &mut Vec<u8>);
+
+        /// Returns whether every element of `bytes`, in native order, is
+        /// valid.
+        fn is_valid(_bytes: &[u8]) -> bool {
+            true
+        }
+    }
+}
+
+/// A type of element of the matrices read from and written to NumPy
+/// `.npy` files: [`f32`], [`f64`], [`i32`], [`i64`], [`u8`] or [`bool`].
+pub trait NpyElement: private::Sealed + Copy {}
+
+macro_rules! impl_npy_element {
+    ($($t:ty => $dtype:literal),*) => {$(
+        impl private::Sealed for $t {
+            const DTYPE: &'static str = $dtype;
+
+            fn from_ne_bytes(bytes: &[u8]) -> Option<Self> {
+                Some(<$t>::from_ne_bytes(bytes.try_into().ok()?))
+            }
+
+            fn extend_ne_bytes(self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_ne_bytes());
+            }
+        }
+
+        impl NpyElement for $t {}
+    )*};
+}
+
+impl_npy_element!(f32 => "f4", f64 => "f8", i32 => "i4", i64 => "i8", u8 => "u1");
+
+impl private::Sealed for bool {
+    const DTYPE: &'static str = "b1";
+
+    fn from_ne_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
+    }
+
+    fn extend_ne_bytes(self, out: &mut Vec<u8>) {
+        out.push(self as u8);
+    }
+
+    fn is_valid(bytes: &[u8]) -> bool {
+        bytes.iter().all(|&byte| byte <= 1)
+    }
+}
+
+impl NpyElement for bool {}
+
+/// The header of a `.npy` file.
+struct Header {
+    num_rows: usize,
+    num_columns: usize,
+    layout: Layout,
+    /// Whether the data is in the opposite byte order to the native one.
+    swap: bool,
+}
+
+impl Header {
+    /// Reads the header of a `.npy` file of elements of type `T`, leaving
+    /// `reader` at the start of the data.
+    fn read<T: NpyElement, R: Read>(reader: &mut R) -> Result<Self, MatrixError> {
+        let mut prelude = [0; 8];
+        reader.read_exact(&mut prelude)?;
+        if &prelude[..6] != MAGIC {
+            return Err(format_error("not a .npy file"));
+        }
+        let header_len = match prelude[6] {
+            1 => {
+                let mut len = [0; 2];
+                reader.read_exact(&mut len)?;
+                u16::from_le_bytes(len) as usize
+            }
+            2 | 3 => {
+                let mut len = [0; 4];
+                reader.read_exact(&mut len)?;
+                u32::from_le_bytes(len) as usize
+            }
+            major => {
+                let message = format!("unsupported .npy version {major}.{}", prelude[7]);
+                return Err(format_error(message));
+            }
+        };
+        let mut header = vec![0; header_len];
+        reader.read_exact(&mut header)?;
+        let header =
+            String::from_utf8(header).map_err(|_| format_error("the header is not valid text"))?;
+
+        let descr = match value(&header, "descr") {
+            Some(descr) if descr.starts_with('[') => {
+                return Err(format_error("structured dtypes are not supported"));
+            }
+            Some(descr) => quoted(descr),
+            None => None,
+        }
+        .ok_or_else(|| format_error("the header has no `descr`"))?;
+        let (swap, dtype) = match descr.as_bytes().first() {
+            Some(b'<') => (cfg!(target_endian = "big"), &descr[1..]),
+            Some(b'>') => (cfg!(target_endian = "little"), &descr[1..]),
+            Some(b'|' | b'=') => (false, &descr[1..]),
+            _ => (false, descr),
+        };
+        check_dtype::<T>(descr, dtype)?;
+
+        let layout = match value(&header, "fortran_order") {
+            Some(value) if value.starts_with("True") => Layout::ColumnMajor,
+            Some(value) if value.starts_with("False") => Layout::RowMajor,
+            _ => return Err(format_error("the header has no `fortran_order`")),
+        };
+
+        let shape = value(&header, "shape")
+            .and_then(|value| value.strip_prefix('('))
+            .and_then(|value| value.split_once(')'))
+            .and_then(|(shape, _)| {
+                let dims = shape
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|dim| !dim.is_empty());
+                dims.map(|dim| dim.parse().ok())
+                    .collect::<Option<Vec<usize>>>()
+            })
+            .ok_or_else(|| format_error("the header has no valid `shape`"))?;
+        let (num_rows, num_columns) = match shape[..] {
+            [] => (1, 1),
+            [num_columns] => (1, num_columns),
+            [num_rows, num_columns] => (num_rows, num_columns),
+            _ => {
+                let message = format!("expected a two-dimensional array, found shape {shape:?}");
+                return Err(format_error(message));
+            }
+        };
+
+        Ok(Self {
+            num_rows,
+            num_columns,
+            layout,
+            swap,
+        })
+    }
+
+    /// Returns the number of bytes of data of elements of type `T`.
+    fn num_bytes<T>(&self) -> Result<usize, MatrixError> {
+        self.num_rows
+            .checked_mul(self.num_columns)
+            .and_then(|len| len.checked_mul(mem::size_of::<T>()))
+            .ok_or_else(|| format_error("the array is too large"))
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: NpyElement,
+{
+    /// Reads a matrix from a NumPy `.npy` file.
+    ///
+    /// Files of versions 1.0, 2.0 and 3.0, in either byte order, are
+    /// supported. Their C or Fortran order gives the [`Layout`] of the
+    /// matrix. One-dimensional arrays are read as a single row.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Format`] if the file is malformed, if its
+    /// dtype is not the one of `T` or if it has more than two dimensions,
+    /// and [`MatrixError::Io`] if reading fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matrix::Matrix;
+    ///
+    /// let a: Matrix<f64> = Matrix::from([[1., 2.], [3., 4.]]);
+    /// let mut npy = Vec::new();
+    /// a.write_npy(&mut npy)?;
+    ///
+    /// assert_eq!(Matrix::read_npy(&npy[..])?, a);
+    /// assert!(Matrix::<f32>::read_npy(&npy[..]).is_err());
+    /// # Ok::<(), matrix::MatrixError>(())
+    /// ```
+    pub fn read_npy<R: Read>(mut reader: R) -> Result<Self, MatrixError> {
+        let header = Header::read::<T, _>(&mut reader)?;
+        let data = read_elements(&mut reader, header.num_bytes::<T>()?, header.swap, |_| ())?;
+        let Header {
+            num_rows,
+            num_columns,
+            layout,
+            ..
+        } = header;
+        Ok(Self::from_layout(num_rows, num_columns, data, layout))
+    }
+}
+
+impl<'a, T> Matrix<T, Cow<'a, [T]>>
+where
+    T: NpyElement,
+{
+    /// Reads a matrix from the bytes of a NumPy `.npy` file, such as a
+    /// memory-mapped one, as [`Matrix::read_npy`] does.
+    ///
+    /// The matrix borrows its elements from `bytes` when they are in native
+    /// byte order and suitably aligned for `T`, which NumPy ensures for
+    /// buffers aligned to 64 bytes, and copies them otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`Matrix::read_npy`] does, and returns
+    /// [`MatrixError::Format`] if `bytes` holds more data than the header
+    /// describes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use matrix::Matrix;
+    /// use std::borrow::Cow;
+    ///
+    /// let mut npy = Vec::new();
+    /// Matrix::from([[1u8, 2], [3, 4]]).write_npy(&mut npy)?;
+    /// let a = Matrix::<u8, _>::from_npy_bytes(&npy)?;
+    ///
+    /// assert!(matches!(a.into_storage(), Cow::Borrowed([1, 2, 3, 4])));
+    /// # Ok::<(), matrix::MatrixError>(())
+    /// ```
+    pub fn from_npy_bytes(bytes: &'a [u8]) -> Result<Self, MatrixError> {
+        let mut data = bytes;
+        let header = Header::read::<T, _>(&mut data)?;
+        let num_bytes = header.num_bytes::<T>()?;
+        if data.len() != num_bytes {
+            let message = format!("expected {num_bytes} bytes of data, found {}", data.len());
+            return Err(format_error(message));
+        }
+
+        let aligned = data.as_ptr().align_offset(mem::align_of::<T>()) == 0;
+        let storage = if !header.swap && aligned && T::is_valid(data) {
+            // SAFETY: `data` is aligned for `T` and holds `num_bytes` bytes,
+            // valid values of `T` as checked by `is_valid`.
+            let len = num_bytes / mem::size_of::<T>();
+            Cow::Borrowed(unsafe { slice::from_raw_parts(data.as_ptr().cast::<T>(), len) })
+        } else {
+            let mut elements = Vec::with_capacity(num_bytes / mem::size_of::<T>());
+            decode(data, header.swap, &mut elements)?;
+            Cow::Owned(elements)
+        };
+        Ok(Self::from_layout(
+            header.num_rows,
+            header.num_columns,
+            storage,
+            header.layout,
+        ))
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    T: NpyElement,
+    S: Storage<T>,
+{
+    /// Writes the matrix to a NumPy `.npy` file, in native byte order and
+    /// in C or Fortran order according to its [`Layout`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Io`] if writing fails.
+    pub fn write_npy<W: Write>(&self, writer: W) -> Result<(), MatrixError> {
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(&self.npy_header())?;
+        let mut bytes = Vec::new();
+        for lane in self.lanes() {
+            bytes.clear();
+            for &x in lane {
+                x.extend_ne_bytes(&mut bytes);
+            }
+            writer.write_all(&bytes)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the number of bytes that [`write_npy`] writes.
+    ///
+    /// [`write_npy`]: Matrix::write_npy
+    pub(crate) fn npy_len(&self) -> usize {
+        self.npy_header().len() + self.num_rows * self.num_columns * mem::size_of::<T>()
+    }
+
+    /// Returns the magic string, version, header length and header of the
+    /// `.npy` file of the matrix, padded to a multiple of 64 bytes.
+    fn npy_header(&self) -> Vec<u8> {
+        let byte_order = match mem::size_of::<T>() {
+            1 => '|',
+            _ if cfg!(target_endian = "little") => '<',
+            _ => '>',
+        };
+        let fortran_order = match self.layout {
+            Layout::RowMajor => "False",
+            Layout::ColumnMajor => "True",
+        };
+        let mut header = format!(
+            "{{'descr': '{byte_order}{}', 'fortran_order': {fortran_order}, 'shape': ({}, {}), }}",
+            T::DTYPE,
+            self.num_rows,
+            self.num_columns,
+        );
+
+        // The header ends with a newline, after the padding spaces.
+        let prelude_len = if header.len() + 64 <= u16::MAX as usize {
+            10
+        } else {
+            12
+        };
+        let padding = (64 - (prelude_len + header.len() + 1) % 64) % 64;
+        header.extend(std::iter::repeat_n(' ', padding));
+        header.push('\n');
+
+        let mut bytes = MAGIC.to_vec();
+        if prelude_len == 10 {
+            bytes.extend_from_slice(&[1, 0]);
+            bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        } else {
+            bytes.extend_from_slice(&[2, 0]);
+            bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        }
+        bytes.extend_from_slice(header.as_bytes());
+        bytes
+    }
+}
+
+/// Reads `num_bytes` bytes of elements from `reader`, in chunks that are
+/// passed to `inspect` before being decoded, swapping the bytes of each
+/// element if `swap`.
+///
+/// The capacity of the result grows as the elements are read, so that a
+/// malformed header cannot make it allocate more memory than the input
+/// holds.
+pub(crate) fn read_elements<T, R, F>(
+    reader: &mut R,
+    num_bytes: usize,
+    swap: bool,
+    mut inspect: F,
+) -> Result<Vec<T>, MatrixError>
+where
+    T: NpyElement,
+    R: Read,
+    F: FnMut(&[u8]),
+{
+    let mut remaining = num_bytes;
+    let mut data = Vec::with_capacity(remaining.min(CHUNK_BYTES) / mem::size_of::<T>());
+    let mut buffer = vec![0; remaining.min(CHUNK_BYTES)];
+    while remaining > 0 {
+        let chunk = &mut buffer[..remaining.min(CHUNK_BYTES)];
+        reader.read_exact(chunk)?;
+        inspect(chunk);
+        decode(chunk, swap, &mut data)?;
+        remaining -= chunk.len();
+    }
+    Ok(data)
+}
+
+/// Returns an error unless `dtype`, the kind and size of `descr`, is the
+/// dtype of `T`.
+pub(crate) fn check_dtype<T: NpyElement>(descr: &str, dtype: &str) -> Result<(), MatrixError> {
+    if dtype == T::DTYPE {
+        return Ok(());
+    }
+    let message = if DTYPES.contains(&dtype) {
+        format!(
+            "cannot read `{descr}` data into a matrix of `{}`",
+            any::type_name::<T>()
+        )
+    } else {
+        let supported = DTYPES.map(|dtype| format!("`{dtype}`")).join(", ");
+        format!("unsupported dtype `{descr}`, expected one of {supported}")
+    };
+    Err(format_error(message))
+}
+
+/// Decodes the elements of `data`, swapping the bytes of each one if
+/// `swap`, and appends them to `out`.
+pub(crate) fn decode<T: NpyElement>(
+    data: &[u8],
+    swap: bool,
+    out: &mut Vec<T>,
+) -> Result<(), MatrixError> {
+    let size = mem::size_of::<T>();
+    let mut bytes = [0; 8];
+    for chunk in data.chunks_exact(size) {
+        let bytes = &mut bytes[..size];
+        bytes.copy_from_slice(chunk);
+        if swap {
+            bytes.reverse();
+        }
+        let x = T::from_ne_bytes(bytes).ok_or_else(|| {
+            let message = format!("invalid `{}` value {bytes:?}", any::type_name::<T>());
+            format_error(message)
+        })?;
+        out.push(x);
+    }
+    Ok(())
+}
+
+/// Returns the text that follows `key` and its colon in the Python
+/// dictionary `header`.
+fn value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    ["'", "\""].iter().find_map(|quote| {
+        let start = header.find(&format!("{quote}{key}{quote}"))? + key.len() + 2;
+        let rest = header[start..].trim_start().strip_prefix(':')?;
+        Some(rest.trim_start())
+    })
+}
+
+/// Returns the contents of the Python string literal at the start of
+/// `text`.
+fn quoted(text: &str) -> Option<&str> {
+    let quote = text.chars().next().filter(|&c| c == '\'' || c == '"')?;
+    let rest = &text[1..];
+    rest.find(quote).map(|end| &rest[..end])
+}
+
+pub(crate) fn format_error(message: impl Into<String>) -> MatrixError {
+    MatrixError::Format {
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod test_npy {
+    use crate::{Layout, Matrix, MatrixError, NpyElement};
+    use std::borrow::Cow;
+
+    /// Returns a version 1.0 `.npy` file with the given header and data.
+    fn npy(header: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = header.to_string();
+        while !(10 + header.len() + 1).is_multiple_of(64) {
+            header.push(' ');
+        }
+        header.push('\n');
+        let mut npy = b"\x93NUMPY\x01\x00".to_vec();
+        npy.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        npy.extend_from_slice(header.as_bytes());
+        npy.extend_from_slice(data);
+        npy
+    }
+
+    fn round_trip<T: NpyElement + PartialEq + std::fmt::Debug>(a: &Matrix<T>) {
+        let mut npy = Vec::new();
+        a.write_npy(&mut npy).unwrap();
+
+        assert_eq!(npy.len(), a.npy_len());
+        assert_eq!(&Matrix::<T>::read_npy(&npy[..]).unwrap(), a);
+        assert_eq!(Matrix::from_npy_bytes(&npy).unwrap().to_owned_matrix(), *a);
+    }
+
+    fn format_error<T: NpyElement + std::fmt::Debug>(npy: &[u8]) -> String {
+        match Matrix::<T>::read_npy(npy) {
+            Err(MatrixError::Format { message }) => message,
+            result => panic!("expected a format error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn types() {
+        round_trip(&Matrix::from([[1.5f32, -2.], [0., f32::MAX]]));
+        round_trip(&Matrix::from([[1.5f64, -2.], [0., f64::MIN_POSITIVE]]));
+        round_trip(&Matrix::from([[1i32, -2, i32::MIN]]));
+        round_trip(&Matrix::from([[1i64], [i64::MAX]]));
+        round_trip(&Matrix::from([[1u8, 255], [0, 7]]));
+        round_trip(&Matrix::from([[true, false], [false, true]]));
+        round_trip(&Matrix::<f64>::zeros(0, 3));
+        round_trip(&Matrix::from([[1., 2., 3.], [4., 5., 6.]]).into_layout(Layout::ColumnMajor));
+    }
+
+    #[test]
+    fn header() {
+        let mut npy = Vec::new();
+        Matrix::from([[1i32, 2, 3], [4, 5, 6]])
+            .write_npy(&mut npy)
+            .unwrap();
+
+        let expected = "{'descr': '<i4', 'fortran_order': False, 'shape': (2, 3), }";
+        assert_eq!(&npy[..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        assert!(npy[10..].starts_with(expected.as_bytes()));
+        assert_eq!(npy[9 + header_len], b'\n');
+        assert_eq!(npy.len(), 10 + header_len + 6 * 4);
+    }
+
+    #[test]
+    fn big_endian_fortran_order() {
+        // `np.arange(6, dtype='>i4').reshape(2, 3, order='F')`.
+        let data: Vec<u8> = (0..6i32).flat_map(i32::to_be_bytes).collect();
+        let npy = npy(
+            "{'descr': '>i4', 'fortran_order': True, 'shape': (2, 3), }",
+            &data,
+        );
+        let expected = Matrix::from([[0, 2, 4], [1, 3, 5]]);
+        let a: Matrix<i32> = Matrix::read_npy(&npy[..]).unwrap();
+        let b = Matrix::<i32, Cow<[i32]>>::from_npy_bytes(&npy).unwrap();
+
+        assert_eq!(a, expected);
+        assert_eq!(a.layout(), Layout::ColumnMajor);
+        assert_eq!(b.to_owned_matrix(), expected);
+        if cfg!(target_endian = "little") {
+            assert!(matches!(b.into_storage(), Cow::Owned(_)));
+        }
+    }
+
+    #[test]
+    fn versions_and_shapes() {
+        let data: Vec<u8> = [1.5f32, 2.5].iter().flat_map(|x| x.to_le_bytes()).collect();
+        let v1 = npy(
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (2,), }",
+            &data,
+        );
+        let mut v2 = v1[..8].to_vec();
+        v2[6] = 2;
+        v2.extend_from_slice(&u32::from(u16::from_le_bytes([v1[8], v1[9]])).to_le_bytes());
+        v2.extend_from_slice(&v1[10..]);
+
+        let expected = Matrix::from([[1.5f32, 2.5]]);
+        assert_eq!(Matrix::read_npy(&v1[..]).unwrap(), expected);
+        assert_eq!(Matrix::read_npy(&v2[..]).unwrap(), expected);
+        let column = npy(
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (2, 1), }",
+            &data,
+        );
+        assert_eq!(
+            Matrix::read_npy(&column[..]).unwrap(),
+            Matrix::from([[1.5f32], [2.5]])
+        );
+    }
+
+    #[test]
+    fn zero_copy() {
+        let a: Matrix<f64> = Matrix::from([[1., 2.], [3., 4.]]);
+        let mut npy = Vec::new();
+        a.write_npy(&mut npy).unwrap();
+        // Copies the file to a buffer aligned to 8 bytes.
+        let mut aligned = vec![0u64; npy.len() / 8];
+        let bytes =
+            unsafe { std::slice::from_raw_parts_mut(aligned.as_mut_ptr().cast(), npy.len()) };
+        bytes.copy_from_slice(&npy);
+
+        let b = Matrix::<f64, Cow<[f64]>>::from_npy_bytes(bytes).unwrap();
+        assert!(matches!(b.into_storage(), Cow::Borrowed(_)));
+
+        let mut misaligned = vec![0; npy.len() + 1];
+        misaligned[1..].copy_from_slice(bytes);
+        let c = Matrix::<f64, Cow<[f64]>>::from_npy_bytes(&misaligned[1..]).unwrap();
+        assert_eq!(c.to_owned_matrix(), a);
+        assert!(matches!(c.into_storage(), Cow::Owned(_)));
+    }
+
+    #[test]
+    fn errors() {
+        let mut file = Vec::new();
+        Matrix::from([[1.5f64]]).write_npy(&mut file).unwrap();
+
+        assert_eq!(
+            format_error::<f32>(&file),
+            "cannot read `<f8` data into a matrix of `f32`"
+        );
+        let complex = npy(
+            "{'descr': '<c8', 'fortran_order': False, 'shape': (1, 1), }",
+            &[0; 8],
+        );
+        assert_eq!(
+            format_error::<f64>(&complex),
+            "unsupported dtype `<c8`, expected one of `f4`, `f8`, `i4`, `i8`, `u1`, `b1`"
+        );
+        assert_eq!(
+            format_error::<f64>(b"PK\x03\x04 zip file"),
+            "not a .npy file"
+        );
+        let three_d = npy(
+            "{'descr': '<f8', 'fortran_order': False, 'shape': (1, 1, 1), }",
+            &[],
+        );
+        assert_eq!(
+            format_error::<f64>(&three_d),
+            "expected a two-dimensional array, found shape [1, 1, 1]"
+        );
+        assert!(matches!(
+            Matrix::<f64>::read_npy(&file[..file.len() - 1]),
+            Err(MatrixError::Io(_))
+        ));
+
+        let mut flags = Vec::new();
+        Matrix::from([[true]]).write_npy(&mut flags).unwrap();
+        *flags.last_mut().unwrap() = 2;
+        assert_eq!(format_error::<bool>(&flags), "invalid `bool` value [2]");
+    }
+}
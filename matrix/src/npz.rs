@@ -0,0 +1,172 @@
+use super::npy::format_error;
+use super::{Matrix, MatrixError, NpyElement, Storage};
+use std::io::{Read, Seek, Write};
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// A reader of the named matrices of a NumPy `.npz` archive, as written by
+/// `np.savez` or `np.savez_compressed`.
+///
+/// # Examples
+///
+/// ```
+/// use matrix::{Matrix, NpzReader, NpzWriter};
+/// use std::io::Cursor;
+///
+/// let mut npz = NpzWriter::new(Cursor::new(Vec::new()));
+/// npz.write("weights", &Matrix::from([[0.5f32, -1.], [2., 0.]]))?;
+/// npz.write("counts", &Matrix::from([[1i64, 2, 3]]))?;
+/// let file = npz.finish()?;
+///
+/// let mut npz = NpzReader::new(file)?;
+/// assert_eq!(npz.names().collect::<Vec<_>>(), ["weights", "counts"]);
+/// assert_eq!(npz.read::<i64>("counts")?, Matrix::from([[1, 2, 3]]));
+/// # Ok::<(), matrix::MatrixError>(())
+/// ```
+pub struct NpzReader<R> {
+    archive: ZipArchive<R>,
+}
+
+impl<R: Read + Seek> NpzReader<R> {
+    /// Opens the archive in `reader`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Format`] if `reader` does not hold a zip
+    /// archive, and [`MatrixError::Io`] if reading fails.
+    pub fn new(reader: R) -> Result<Self, MatrixError> {
+        let archive = ZipArchive::new(reader).map_err(zip_error)?;
+        Ok(Self { archive })
+    }
+
+    /// Returns an iterator over the names of the matrices of the archive,
+    /// in the order they were written.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.archive
+            .file_names()
+            .filter_map(|name| name.strip_suffix(".npy"))
+    }
+
+    /// Reads the matrix called `name`, as [`Matrix::read_npy`] does.
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`Matrix::read_npy`] does, and returns
+    /// [`MatrixError::Format`] if the archive has no matrix called `name`.
+    pub fn read<T: NpyElement>(&mut self, name: &str) -> Result<Matrix<T>, MatrixError> {
+        match self.archive.by_name(&format!("{name}.npy")) {
+            Ok(file) => Matrix::read_npy(file),
+            Err(ZipError::FileNotFound) => Err(format_error(format!(
+                "the archive has no matrix called `{name}`"
+            ))),
+            Err(error) => Err(zip_error(error)),
+        }
+    }
+}
+
+/// A writer of NumPy `.npz` archives of named matrices.
+///
+/// See [`NpzReader`] for an example.
+pub struct NpzWriter<W: Write + Seek> {
+    archive: ZipWriter<W>,
+    options: SimpleFileOptions,
+}
+
+impl<W: Write + Seek> NpzWriter<W> {
+    /// Starts an archive in `writer` that stores its matrices uncompressed,
+    /// as `np.savez` does.
+    pub fn new(writer: W) -> Self {
+        Self::with_compression(writer, CompressionMethod::Stored)
+    }
+
+    /// Starts an archive in `writer` that compresses its matrices, as
+    /// `np.savez_compressed` does.
+    pub fn compressed(writer: W) -> Self {
+        Self::with_compression(writer, CompressionMethod::Deflated)
+    }
+
+    fn with_compression(writer: W, method: CompressionMethod) -> Self {
+        Self {
+            archive: ZipWriter::new(writer),
+            options: SimpleFileOptions::default().compression_method(method),
+        }
+    }
+
+    /// Adds `matrix` to the archive, with the given name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Io`] if writing fails, and
+    /// [`MatrixError::Format`] if the archive cannot hold the matrix.
+    pub fn write<T, S>(&mut self, name: &str, matrix: &Matrix<T, S>) -> Result<(), MatrixError>
+    where
+        T: NpyElement,
+        S: Storage<T>,
+    {
+        let large_file = matrix.npy_len() >= u32::MAX as usize;
+        let options = self.options.large_file(large_file);
+        self.archive
+            .start_file(format!("{name}.npy"), options)
+            .map_err(zip_error)?;
+        matrix.write_npy(&mut self.archive)
+    }
+
+    /// Writes the directory of the archive, and returns the writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatrixError::Io`] if writing fails.
+    pub fn finish(self) -> Result<W, MatrixError> {
+        self.archive.finish().map_err(zip_error)
+    }
+}
+
+fn zip_error(error: ZipError) -> MatrixError {
+    match error {
+        ZipError::Io(error) => MatrixError::Io(error),
+        error => format_error(format!("invalid .npz archive: {error}")),
+    }
+}
+
+#[cfg(test)]
+mod test_npz {
+    use crate::{Layout, Matrix, MatrixError, NpzReader, NpzWriter};
+    use std::io::Cursor;
+
+    #[test]
+    fn round_trip() {
+        let a: Matrix<f64> = Matrix::random_normal(20, 30, 0., 1., 0);
+        let b = Matrix::from([[true, false, true]]).into_layout(Layout::ColumnMajor);
+
+        for compressed in [false, true] {
+            let file = Cursor::new(Vec::new());
+            let mut npz = match compressed {
+                false => NpzWriter::new(file),
+                true => NpzWriter::compressed(file),
+            };
+            npz.write("a", &a).unwrap();
+            npz.write("b", &b).unwrap();
+            let file = npz.finish().unwrap();
+
+            let mut npz = NpzReader::new(file).unwrap();
+            assert_eq!(npz.read::<f64>("a").unwrap(), a);
+            assert_eq!(npz.read::<bool>("b").unwrap(), b);
+            assert!(matches!(
+                npz.read::<f64>("c"),
+                Err(MatrixError::Format { message }) if message == "the archive has no matrix called `c`"
+            ));
+        }
+    }
+
+    #[test]
+    fn not_an_archive() {
+        let mut npy = Vec::new();
+        Matrix::from([[1u8]]).write_npy(&mut npy).unwrap();
+
+        assert!(matches!(
+            NpzReader::new(Cursor::new(npy)),
+            Err(MatrixError::Format { .. })
+        ));
+    }
+}
@@ -1,17 +1,23 @@
-use super::Matrix;
+use super::{Layout, Matrix, Storage};
 use num_traits::Zero;
 use rayon::prelude::*;
 use std::ops::{AddAssign, Mul};
 
 impl<T> Matrix<T> {
-    /// Multiplies matrix `a` by matrix `b`, producing `c = a * b`.
+    /// Multiplies matrix `a` by matrix `b`, producing `c = a * b`. `a` and
+    /// `b` may have any storage.
+    ///
+    /// `c` is column-major if both `a` and `b` are, and row-major
+    /// otherwise.
     ///
     /// # Panics
     ///
     /// Panics if `a.num_columns() != b.num_rows()`.
-    pub fn mul(a: &Self, b: &Self) -> Self
+    pub fn mul<SA, SB>(a: &Matrix<T, SA>, b: &Matrix<T, SB>) -> Self
     where
         T: Copy + AddAssign + Mul<Output = T> + Zero + Sync + Send,
+        SA: Storage<T>,
+        SB: Storage<T>,
     {
         #[cold]
         #[inline(never)]
@@ -27,25 +33,76 @@ impl<T> Matrix<T> {
             assert_failed(a.num_columns(), b.num_rows());
         }
 
+        // Borrowed views are `Sync` whatever the storages of `a` and `b`.
+        let (a, b) = (a.view(), b.view());
         let mut c: Matrix<T> = Matrix::zeros(a.num_rows(), b.num_columns());
 
-        c.rows_mut()
-            .zip(a.rows())
-            .par_bridge()
-            .for_each(|(ci, ai)| {
-                b.rows().zip(ai.iter()).for_each(|(bk, aik)| {
-                    ci.iter_mut().zip(bk.iter()).for_each(|(cij, bkj)| {
-                        (*cij) += (*aik) * (*bkj);
+        // Each combination of layouts gets a loop order whose innermost loop
+        // iterates over contiguous data.
+        match (a.layout(), b.layout()) {
+            (Layout::RowMajor, Layout::RowMajor) => {
+                // Row `i` of `c` is the sum of the rows of `b` scaled by the
+                // elements of row `i` of `a`.
+                c.rows_mut()
+                    .zip(a.rows())
+                    .par_bridge()
+                    .for_each(|(ci, ai)| {
+                        b.rows().zip(ai.iter()).for_each(|(bk, aik)| {
+                            ci.iter_mut().zip(bk.iter()).for_each(|(cij, bkj)| {
+                                (*cij) += (*aik) * (*bkj);
+                            })
+                        })
+                    });
+            }
+            (Layout::ColumnMajor, Layout::ColumnMajor) => {
+                // Column `j` of `c` is the sum of the columns of `a` scaled
+                // by the elements of column `j` of `b`.
+                c = c.into_layout(Layout::ColumnMajor);
+                c.columns_mut()
+                    .zip(b.columns())
+                    .par_bridge()
+                    .for_each(|(cj, bj)| {
+                        a.columns().zip(bj.iter()).for_each(|(ak, bkj)| {
+                            cj.iter_mut().zip(ak.iter()).for_each(|(cij, aik)| {
+                                (*cij) += (*aik) * (*bkj);
+                            })
+                        })
+                    });
+            }
+            (Layout::RowMajor, Layout::ColumnMajor) => {
+                // Element `(i, j)` of `c` is the dot product of row `i` of
+                // `a` and column `j` of `b`.
+                c.rows_mut()
+                    .zip(a.rows())
+                    .par_bridge()
+                    .for_each(|(ci, ai)| {
+                        ci.iter_mut().zip(b.columns()).for_each(|(cij, bj)| {
+                            ai.iter().zip(bj.iter()).for_each(|(aik, bkj)| {
+                                (*cij) += (*aik) * (*bkj);
+                            })
+                        })
+                    });
+            }
+            (Layout::ColumnMajor, Layout::RowMajor) => {
+                // As in the row-major case, but the elements of row `i` of
+                // `a` are one column apart.
+                c.rows_mut().enumerate().par_bridge().for_each(|(i, ci)| {
+                    b.rows().zip(a.columns()).for_each(|(bk, ak)| {
+                        let aik = ak[i];
+                        ci.iter_mut().zip(bk.iter()).for_each(|(cij, bkj)| {
+                            (*cij) += aik * (*bkj);
+                        })
                     })
-                })
-            });
+                });
+            }
+        }
         c
     }
 }
 
 #[cfg(test)]
 mod test_mul {
-    use super::Matrix;
+    use super::{Layout, Matrix};
 
     #[test]
     #[should_panic(expected = "`a.num_columns()` (is 2) \
@@ -66,4 +123,40 @@ mod test_mul {
         assert_eq!(c.num_columns(), b.num_columns());
         assert_eq!(c, Matrix::from([[7.], [33.], [59.]]));
     }
+
+    #[test]
+    fn layouts() {
+        let a: Matrix<f32> = Matrix::from([[0., 1.], [2., 3.], [4., 5.]]);
+        let b: Matrix<f32> = Matrix::from([[6., 7., 8.], [9., 10., 11.]]);
+        let expected = Matrix::mul(&a, &b);
+
+        for a_layout in [Layout::RowMajor, Layout::ColumnMajor] {
+            for b_layout in [Layout::RowMajor, Layout::ColumnMajor] {
+                let c = Matrix::mul(&a.to_layout(a_layout), &b.to_layout(b_layout));
+                let c_layout = match (a_layout, b_layout) {
+                    (Layout::ColumnMajor, Layout::ColumnMajor) => Layout::ColumnMajor,
+                    _ => Layout::RowMajor,
+                };
+
+                assert_eq!(c, expected);
+                assert_eq!(c.layout(), c_layout);
+            }
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let a: Matrix<f32> = Matrix::zeros(3, 0);
+        let b: Matrix<f32> = Matrix::zeros(0, 2);
+
+        for a_layout in [Layout::RowMajor, Layout::ColumnMajor] {
+            for b_layout in [Layout::RowMajor, Layout::ColumnMajor] {
+                let (a, b) = (a.to_layout(a_layout), b.to_layout(b_layout));
+
+                assert_eq!(Matrix::mul(&a, &b), Matrix::zeros(3, 2));
+                assert_eq!(Matrix::mul(&b, &Matrix::zeros(2, 4)), Matrix::zeros(0, 4));
+                assert_eq!(Matrix::mul(&Matrix::zeros(4, 3), &a), Matrix::zeros(4, 0));
+            }
+        }
+    }
 }
@@ -0,0 +1,340 @@
+use super::Matrix;
+use num_traits::Float;
+use rayon::prelude::*;
+
+/// A summation policy for floating point products and reductions.
+///
+/// Adding `n` floating point values one after the other, as [`Matrix::mul`]
+/// does, can produce an error that grows linearly with `n`. The policies
+/// below trade speed for accuracy:
+///
+/// - [`Standard`] adds the values one after the other;
+/// - [`Pairwise`] adds them in a balanced tree, so the error grows with
+///   `log(n)` at nearly no extra cost;
+/// - [`Kahan`] keeps a running compensation of the rounding errors
+///   (second-order Kahan-Babuška summation), so the error does not grow
+///   with `n`, at the cost of seven times more additions.
+pub trait Precision: Copy + Send + Sync {
+    /// Adds `bk` scaled by `aik` to `ci` for every pair `(aik, bk)` of
+    /// elements of `ai` and rows of `b`, that is, computes `ci += ai * b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `b` is not row-major.
+    fn mul_row<T>(self, ci: &mut [T], ai: &[T], b: &Matrix<T>)
+    where
+        T: Float;
+
+    /// Returns the sum of `values`.
+    fn sum<T>(self, values: &[T]) -> T
+    where
+        T: Float;
+}
+
+/// Adds the values one after the other. See [`Precision`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Standard;
+
+/// Adds the values in a balanced tree. See [`Precision`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pairwise;
+
+/// Compensates the rounding errors of each addition. See [`Precision`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Kahan;
+
+/// Number of values below which [`Pairwise`] adds them one after the other.
+const PAIRWISE_BLOCK_LEN: usize = 128;
+
+impl Precision for Standard {
+    fn mul_row<T>(self, ci: &mut [T], ai: &[T], b: &Matrix<T>)
+    where
+        T: Float,
+    {
+        b.rows().zip(ai.iter()).for_each(|(bk, aik)| {
+            ci.iter_mut().zip(bk.iter()).for_each(|(cij, bkj)| {
+                (*cij) = (*cij) + (*aik) * (*bkj);
+            })
+        });
+    }
+
+    fn sum<T>(self, values: &[T]) -> T
+    where
+        T: Float,
+    {
+        values.iter().fold(T::zero(), |sum, &x| sum + x)
+    }
+}
+
+impl Precision for Pairwise {
+    fn mul_row<T>(self, ci: &mut [T], ai: &[T], b: &Matrix<T>)
+    where
+        T: Float,
+    {
+        fn pairwise<T: Float>(ci: &mut [T], ai: &[T], b: &[&[T]]) {
+            if ai.len() <= PAIRWISE_BLOCK_LEN {
+                ai.iter().zip(b).for_each(|(aik, bk)| {
+                    ci.iter_mut().zip(bk.iter()).for_each(|(cij, bkj)| {
+                        (*cij) = (*cij) + (*aik) * (*bkj);
+                    })
+                });
+            } else {
+                let half = ai.len() / 2;
+                let (b_head, b_tail) = b.split_at(half);
+                let mut tail = vec![T::zero(); ci.len()];
+                pairwise(ci, &ai[..half], b_head);
+                pairwise(&mut tail, &ai[half..], b_tail);
+                ci.iter_mut()
+                    .zip(tail)
+                    .for_each(|(cij, tj)| (*cij) = (*cij) + tj);
+            }
+        }
+
+        // Going through `rows` checks the layout of `b`, and skips its
+        // padding.
+        let b: Vec<&[T]> = b.rows().collect();
+        if ci.is_empty() {
+            return;
+        }
+
+        // `ci` may already hold a partial result, which is added last.
+        let mut sum = vec![T::zero(); ci.len()];
+        pairwise(&mut sum, ai, &b);
+        ci.iter_mut()
+            .zip(sum)
+            .for_each(|(cij, sj)| (*cij) = (*cij) + sj);
+    }
+
+    fn sum<T>(self, values: &[T]) -> T
+    where
+        T: Float,
+    {
+        if values.len() <= PAIRWISE_BLOCK_LEN {
+            Standard.sum(values)
+        } else {
+            let (head, tail) = values.split_at(values.len() / 2);
+            self.sum(head) + self.sum(tail)
+        }
+    }
+}
+
+impl Precision for Kahan {
+    fn mul_row<T>(self, ci: &mut [T], ai: &[T], b: &Matrix<T>)
+    where
+        T: Float,
+    {
+        let mut compensations = vec![(T::zero(), T::zero()); ci.len()];
+        b.rows().zip(ai.iter()).for_each(|(bk, aik)| {
+            ci.iter_mut()
+                .zip(compensations.iter_mut())
+                .zip(bk.iter())
+                .for_each(|((cij, compensation), bkj)| {
+                    (*cij, *compensation) = compensated_add(*cij, *compensation, (*aik) * (*bkj));
+                })
+        });
+        ci.iter_mut()
+            .zip(compensations)
+            .for_each(|(cij, (first, second))| (*cij) = (*cij) + (first + second));
+    }
+
+    fn sum<T>(self, values: &[T]) -> T
+    where
+        T: Float,
+    {
+        let (sum, (first, second)) = values.iter().fold(
+            (T::zero(), (T::zero(), T::zero())),
+            |(sum, compensation), &x| compensated_add(sum, compensation, x),
+        );
+        sum + (first + second)
+    }
+}
+
+/// Returns `a + b` and its rounding error, so that both add up to the
+/// exact sum.
+#[inline(always)]
+fn two_sum<T: Float>(a: T, b: T) -> (T, T) {
+    let sum = a + b;
+    let error = if a.abs() >= b.abs() {
+        (a - sum) + b
+    } else {
+        (b - sum) + a
+    };
+    (sum, error)
+}
+
+/// Adds `x` to `sum` with Klein's second-order Kahan-Babuška summation.
+///
+/// The rounding error of each addition goes into the first compensation,
+/// and the rounding error of that, in turn, into the second one. A single
+/// compensation would itself drift away from the true error after
+/// millions of additions of values with the same sign.
+#[inline(always)]
+fn compensated_add<T: Float>(sum: T, (first, second): (T, T), x: T) -> (T, (T, T)) {
+    let (sum, error) = two_sum(sum, x);
+    let (first, first_error) = two_sum(first, error);
+    (sum, (first, second + first_error))
+}
+
+impl<T> Matrix<T>
+where
+    T: Float + Send + Sync,
+{
+    /// Multiplies matrix `a` by matrix `b`, producing `c = a * b`, and
+    /// accumulates the products with the summation policy `precision`.
+    ///
+    /// `Matrix::mul_with(a, b, Standard)` is equivalent to
+    /// `Matrix::mul(a, b)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a.num_columns() != b.num_rows()`.
+    pub fn mul_with<P: Precision>(a: &Self, b: &Self, precision: P) -> Self {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(a_num_columns: usize, b_num_rows: usize) -> ! {
+            panic!(
+                "`a.num_columns()` (is {a_num_columns}) \
+                should be equal to `b.num_rows()` (is {b_num_rows})"
+            );
+        }
+
+        if a.num_columns() != b.num_rows() {
+            assert_failed(a.num_columns(), b.num_rows());
+        }
+
+        let (a, b) = (a.as_row_major(), b.as_row_major());
+
+        let mut c: Matrix<T> = Matrix::zeros(a.num_rows(), b.num_columns());
+
+        c.rows_mut()
+            .zip(a.rows())
+            .par_bridge()
+            .for_each(|(ci, ai)| precision.mul_row(ci, ai, &b));
+        c
+    }
+
+    /// Returns the sum of all elements of the matrix, accumulated with the
+    /// summation policy `precision`.
+    pub fn sum_with<P: Precision>(&self, precision: P) -> T {
+        match self.try_as_flattened() {
+            Ok(values) => precision.sum(values),
+            Err(_) => precision.sum(self.to_layout(self.layout).as_flattened()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_precision {
+    use super::{Kahan, Pairwise, Precision, Standard};
+    use crate::{Layout, Matrix};
+
+    const LEN: usize = 100_000;
+
+    /// Returns a row and a column vector whose product is hard to
+    /// accumulate in `f32`, together with the product computed in `f64`.
+    fn dot_product() -> (Matrix<f32>, Matrix<f32>, f64) {
+        let mut a: Matrix<f32> = Matrix::zeros(1, LEN);
+        let mut b: Matrix<f32> = Matrix::zeros(LEN, 1);
+        for k in 0..LEN {
+            a[0][k] = 1. + (k % 7) as f32 * 0.1;
+            b[k][0] = 0.1 + (k % 3) as f32 * 0.01;
+        }
+        let expected = (0..LEN).map(|k| a[0][k] as f64 * b[k][0] as f64).sum();
+        (a, b, expected)
+    }
+
+    fn relative_error<P: Precision>(precision: P) -> f64 {
+        let (a, b, expected) = dot_product();
+        let c = Matrix::mul_with(&a, &b, precision);
+        ((c[0][0] as f64 - expected) / expected).abs()
+    }
+
+    #[test]
+    fn standard() {
+        let (a, b, _) = dot_product();
+
+        assert_eq!(Matrix::mul_with(&a, &b, Standard), Matrix::mul(&a, &b));
+    }
+
+    #[test]
+    fn pairwise() {
+        let standard = relative_error(Standard);
+        let pairwise = relative_error(Pairwise);
+
+        assert!(pairwise < 1e-6, "relative error is {pairwise}");
+        assert!(pairwise * 10. < standard);
+    }
+
+    #[test]
+    fn kahan() {
+        let standard = relative_error(Standard);
+        let kahan = relative_error(Kahan);
+
+        // The result is the correctly rounded product.
+        assert!(kahan <= f32::EPSILON as f64, "relative error is {kahan}");
+        assert!(kahan * 10. < standard);
+    }
+
+    #[test]
+    fn mul_with_matrices() {
+        let a: Matrix<f64> = Matrix::from([[0., 1.], [2., 3.], [4., 5.]]);
+        let b: Matrix<f64> = Matrix::from([[6.], [7.]]);
+        let c = Matrix::from([[7.], [33.], [59.]]);
+
+        assert_eq!(Matrix::mul_with(&a, &b, Pairwise), c);
+        assert_eq!(Matrix::mul_with(&a, &b, Kahan), c);
+    }
+
+    #[test]
+    fn mul_with_empty_matrices() {
+        let a: Matrix<f32> = Matrix::ones(2, 0);
+        let b: Matrix<f32> = Matrix::ones(0, 3);
+
+        assert_eq!(Matrix::mul_with(&a, &b, Pairwise), Matrix::zeros(2, 3));
+        assert_eq!(
+            Matrix::mul_with(&b, &Matrix::ones(3, 0), Pairwise),
+            Matrix::zeros(0, 0)
+        );
+        assert_eq!(
+            Matrix::mul_with(&Matrix::ones(2, 2), &a, Pairwise),
+            Matrix::zeros(2, 0)
+        );
+        assert_eq!(Matrix::mul_with(&a, &b, Kahan), Matrix::zeros(2, 3));
+        assert_eq!(Matrix::<f32>::zeros(0, 4).sum_with(Kahan), 0.);
+    }
+
+    fn mul_row<P: Precision>(precision: P, b: &Matrix<f64>) -> Vec<f64> {
+        let mut ci = vec![0.; b.num_columns()];
+        precision.mul_row(&mut ci, &[1., 0.], b);
+        ci
+    }
+
+    #[test]
+    fn mul_row_with_row_major() {
+        let b = Matrix::from([[1., 2.], [3., 4.]]);
+
+        assert_eq!(mul_row(Standard, &b), [1., 2.]);
+        assert_eq!(mul_row(Pairwise, &b), [1., 2.]);
+        assert_eq!(mul_row(Kahan, &b), [1., 2.]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`rows` requires a RowMajor matrix (is ColumnMajor)")]
+    fn pairwise_mul_row_with_column_major() {
+        let b = Matrix::from([[1., 2.], [3., 4.]]).into_layout(Layout::ColumnMajor);
+        mul_row(Pairwise, &b);
+    }
+
+    #[test]
+    fn sum_with() {
+        let a: Matrix<f32> = Matrix::full(1000, 100, 0.1);
+        let expected = 1000. * 100. * 0.1f32 as f64;
+
+        for (sum, tolerance) in [(Pairwise.sum(&a.data), 1e-5), (Kahan.sum(&a.data), 1e-7)] {
+            assert!(((sum as f64 - expected) / expected).abs() < tolerance);
+        }
+        assert_eq!(a.sum_with(Kahan), Kahan.sum(a.as_flattened()));
+        assert_eq!(Kahan.sum(&[1e8f32, 1., -1e8, 1.]), 2.);
+    }
+}
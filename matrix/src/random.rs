@@ -0,0 +1,361 @@
+use super::Matrix;
+use num_traits::Float;
+use rayon::prelude::*;
+use std::ops::AddAssign;
+
+impl<T> Matrix<T>
+where
+    T: Float + Send,
+{
+    /// Creates a `Matrix<T>` with shape `(num_rows, num_columns)` whose
+    /// elements are drawn uniformly from the half-open interval
+    /// `[low, high)`, or equal to `low` if `low == high`.
+    ///
+    /// The matrix is generated in parallel, but it depends only on `seed`:
+    /// the same seed gives the same matrix on any number of threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low` or `high` is not finite, or if `low > high`.
+    #[track_caller]
+    pub fn random_uniform(num_rows: usize, num_columns: usize, low: T, high: T, seed: u64) -> Self
+    where
+        T: Sync,
+    {
+        let (low_f64, high_f64) = (low.to_f64().unwrap(), high.to_f64().unwrap());
+        assert_range(low_f64, high_f64);
+        let nonempty = low < high;
+        generate(num_rows, num_columns, seed, |rng| loop {
+            let u = rng.next_f64();
+            let x = from_f64::<T>(low_f64 * (1. - u) + high_f64 * u);
+            // Rounding to `T` may give `high`, which is excluded: such draws
+            // are rejected, which keeps the others uniform.
+            if x < high || !nonempty {
+                return x;
+            }
+        })
+    }
+
+    /// Creates a `Matrix<T>` with shape `(num_rows, num_columns)` whose
+    /// elements are drawn from the normal distribution with mean `mean`
+    /// and standard deviation `std_dev`.
+    ///
+    /// As with [`random_uniform`], the matrix depends only on `seed`.
+    ///
+    /// [`random_uniform`]: Matrix::random_uniform
+    pub fn random_normal(
+        num_rows: usize,
+        num_columns: usize,
+        mean: T,
+        std_dev: T,
+        seed: u64,
+    ) -> Self
+    where
+        T: Sync,
+    {
+        generate(num_rows, num_columns, seed, |rng| {
+            mean + std_dev * from_f64::<T>(rng.next_normal())
+        })
+    }
+
+    /// Creates an `n`-by-`n` random orthogonal matrix, drawn from the Haar
+    /// distribution, that is, uniformly over the orthogonal group.
+    ///
+    /// The matrix is the `Q` factor of the QR decomposition of a matrix of
+    /// standard normal elements, with the signs chosen so that `R` has a
+    /// positive diagonal.
+    pub fn random_orthogonal(n: usize, seed: u64) -> Self {
+        let g = Matrix::<f64>::random_normal(n, n, 0., 1., seed);
+
+        // The rows of `g` are as good as its columns, since its elements are
+        // independent. Gram-Schmidt, run twice on each column to keep the
+        // result orthogonal to working precision, yields exactly the `Q`
+        // whose `R` has a positive diagonal.
+        let mut q: Vec<Vec<f64>> = g.rows().map(<[f64]>::to_vec).collect();
+        for j in 0..n {
+            let (previous, rest) = q.split_at_mut(j);
+            let qj = &mut rest[0];
+            for _ in 0..2 {
+                for qk in previous.iter() {
+                    let r = dot(qk, qj);
+                    qj.iter_mut().zip(qk).for_each(|(x, y)| *x -= r * y);
+                }
+            }
+            let norm = dot(qj, qj).sqrt();
+            qj.iter_mut().for_each(|x| *x /= norm);
+        }
+
+        let data = q.into_iter().flatten().map(from_f64).collect();
+        Self::from_column_major(n, n, data)
+    }
+
+    /// Creates an `n`-by-`n` random symmetric positive definite matrix.
+    ///
+    /// The matrix is `B * B^T / n + I`, where `B` has standard normal
+    /// elements, so its eigenvalues are at least one.
+    pub fn random_spd(n: usize, seed: u64) -> Self
+    where
+        T: AddAssign + Sync,
+    {
+        let b = Self::random_normal(n, n, T::zero(), T::one(), seed);
+        let bt = Self::from_column_major(n, n, b.as_flattened().to_vec());
+        let scale = from_f64::<T>(n as f64);
+
+        let mut a = Self::mul(&b, &bt);
+        a.rows_mut().enumerate().for_each(|(i, ai)| {
+            ai.iter_mut().for_each(|x| *x = *x / scale);
+            ai[i] += T::one();
+        });
+        a
+    }
+
+    /// Creates a `Matrix<T>` with shape `(num_rows, num_columns)` in which
+    /// each element is, independently, nonzero with probability `density`.
+    ///
+    /// The nonzero elements are drawn uniformly from `[0, 1)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `density` is not in `[0, 1]`.
+    #[track_caller]
+    pub fn random_sparse(num_rows: usize, num_columns: usize, density: f64, seed: u64) -> Self
+    where
+        T: Sync,
+    {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(density: f64) -> ! {
+            panic!("`density` (is {density}) should be in [0, 1]");
+        }
+
+        if !(0. ..=1.).contains(&density) {
+            assert_failed(density);
+        }
+
+        generate(num_rows, num_columns, seed, |rng| {
+            let keep = rng.next_f64() < density;
+            let value = rng.next_f64();
+            if keep {
+                from_f64(value)
+            } else {
+                T::zero()
+            }
+        })
+    }
+}
+
+/// Creates a row-major `Matrix<T>` with shape `(num_rows, num_columns)`
+/// whose elements are drawn by `sample`.
+///
+/// Each row gets its own generator, seeded from `seed` and the row index,
+/// so the result does not depend on how the rows are split among threads.
+fn generate<T, F>(num_rows: usize, num_columns: usize, seed: u64, sample: F) -> Matrix<T>
+where
+    T: Send,
+    F: Fn(&mut SplitMix64) -> T + Sync,
+{
+    let data = (0..num_rows)
+        .into_par_iter()
+        .flat_map_iter(|i| {
+            let mut rng = SplitMix64::for_stream(seed, i as u64);
+            (0..num_columns)
+                .map(|_| sample(&mut rng))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    Matrix::from_row_major(num_rows, num_columns, data)
+}
+
+#[track_caller]
+fn assert_range(low: f64, high: f64) {
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_failed(low: f64, high: f64) -> ! {
+        panic!("`low` (is {low}) and `high` (is {high}) should be finite, with `low <= high`");
+    }
+
+    if !(low.is_finite() && high.is_finite() && low <= high) {
+        assert_failed(low, high);
+    }
+}
+
+#[inline]
+fn from_f64<T: Float>(x: f64) -> T {
+    T::from(x).unwrap()
+}
+
+fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y).map(|(a, b)| a * b).sum()
+}
+
+/// The SplitMix64 pseudorandom number generator.
+///
+/// It is small and fast, and its output passes BigCrush. It is not
+/// suitable for cryptography.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    /// Creates the generator for the `stream`-th independent stream of
+    /// `seed`.
+    fn for_stream(seed: u64, stream: u64) -> Self {
+        Self {
+            state: mix(seed ^ mix(stream.wrapping_add(Self::GAMMA))),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(Self::GAMMA);
+        mix(self.state)
+    }
+
+    /// Returns a number drawn uniformly from `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1. / (1u64 << 53) as f64)
+    }
+
+    /// Returns a number drawn from the standard normal distribution, by the
+    /// Box-Muller transform.
+    fn next_normal(&mut self) -> f64 {
+        let u1 = 1. - self.next_f64();
+        let u2 = self.next_f64();
+        (-2. * u1.ln()).sqrt() * (2. * std::f64::consts::PI * u2).cos()
+    }
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod test_random {
+    use crate::{Layout, Matrix};
+
+    #[test]
+    fn reproducible() {
+        let a: Matrix<f64> = Matrix::random_uniform(50, 30, -1., 1., 42);
+        let b = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(|| Matrix::random_uniform(50, 30, -1., 1., 42));
+
+        assert_eq!(a, b);
+        assert_ne!(a, Matrix::random_uniform(50, 30, -1., 1., 43));
+        assert_ne!(a[0], a[1]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`low` (is 0) and `high` (is inf) should be finite, with `low <= high`"
+    )]
+    fn uniform_infinite_range() {
+        Matrix::<f64>::random_uniform(2, 2, 0., f64::INFINITY, 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`low` (is 1) and `high` (is 0) should be finite, with `low <= high`"
+    )]
+    fn uniform_reversed_range() {
+        Matrix::<f32>::random_uniform(2, 2, 1., 0., 0);
+    }
+
+    #[test]
+    fn uniform_narrow_range() {
+        let high = 1.000_000_1;
+        let a: Matrix<f32> = Matrix::random_uniform(200, 200, 1., high, 7);
+        let b: Matrix<f64> = Matrix::random_uniform(200, 200, 1., 1. + f64::EPSILON, 7);
+
+        assert!(a.as_flattened().iter().all(|&x| x == 1.));
+        assert!(b.as_flattened().iter().all(|&x| x == 1.));
+        assert!(Matrix::<f32>::random_uniform(200, 200, -1., 1., 7)
+            .as_flattened()
+            .iter()
+            .all(|&x| x < 1.));
+    }
+
+    #[test]
+    fn uniform() {
+        let a: Matrix<f32> = Matrix::random_uniform(100, 100, 2., 3., 0);
+        let mean = a.as_flattened().iter().sum::<f32>() / 10_000.;
+
+        assert!(a.as_flattened().iter().all(|&x| (2. ..3.).contains(&x)));
+        assert!((mean - 2.5).abs() < 0.01);
+        assert_eq!(
+            Matrix::<f32>::random_uniform(3, 0, 0., 1., 0).shape(),
+            (3, 0)
+        );
+    }
+
+    #[test]
+    fn normal() {
+        let a: Matrix<f64> = Matrix::random_normal(200, 100, 1., 2., 7);
+        let n = 20_000.;
+        let mean = a.as_flattened().iter().sum::<f64>() / n;
+        let variance = a
+            .as_flattened()
+            .iter()
+            .map(|x| (x - mean).powi(2))
+            .sum::<f64>()
+            / n;
+
+        assert!((mean - 1.).abs() < 0.05);
+        assert!((variance - 4.).abs() < 0.2);
+    }
+
+    #[test]
+    fn orthogonal() {
+        let q: Matrix<f64> = Matrix::random_orthogonal(20, 1);
+        let qt = Matrix::from_row_major(
+            20,
+            20,
+            q.to_layout(Layout::ColumnMajor).as_flattened().to_vec(),
+        );
+        let qtq = Matrix::mul(&qt, &q);
+
+        for i in 0..20 {
+            for j in 0..20 {
+                let expected = if i == j { 1. } else { 0. };
+                assert!((qtq[(i, j)] - expected).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn spd() {
+        let a: Matrix<f64> = Matrix::random_spd(10, 3);
+
+        for i in 0..10 {
+            assert!(a[(i, i)] >= 1.);
+            for j in 0..10 {
+                assert_eq!(a[(i, j)], a[(j, i)]);
+            }
+        }
+    }
+
+    #[test]
+    fn sparse() {
+        let a: Matrix<f64> = Matrix::random_sparse(100, 100, 0.1, 5);
+        let nonzeros = a.as_flattened().iter().filter(|&&x| x != 0.).count();
+
+        assert!((800..1200).contains(&nonzeros));
+        assert_eq!(
+            Matrix::<f64>::random_sparse(4, 4, 0., 5),
+            Matrix::zeros(4, 4)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`density` (is 1.5) should be in [0, 1]")]
+    fn sparse_with_invalid_density() {
+        Matrix::<f64>::random_sparse(2, 2, 1.5, 0);
+    }
+}
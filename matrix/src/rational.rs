@@ -0,0 +1,106 @@
+use super::exact::Field;
+use super::Matrix;
+use num_integer::Integer;
+use num_rational::Ratio;
+use std::ops::Neg;
+
+impl<T> Field for Ratio<T> where T: Clone + Integer + Neg<Output = T> {}
+
+impl<T> Matrix<T>
+where
+    T: Clone + Integer,
+{
+    /// Converts the matrix into a matrix of fractions, each one with
+    /// denominator one.
+    pub fn to_ratio(&self) -> Matrix<Ratio<T>> {
+        let data = self.data.iter().cloned().map(Ratio::from_integer).collect();
+        Matrix::from_parts(
+            self.num_rows,
+            self.num_columns,
+            self.leading_dimension,
+            data,
+            self.layout,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_rational {
+    use crate::{BigInt, BigRational, Matrix};
+
+    fn big<const N: usize, const M: usize>(array: [[i64; N]; M]) -> Matrix<BigInt> {
+        Matrix::from(array.map(|row| row.map(BigInt::from)))
+    }
+
+    fn ratio(numerator: i64, denominator: i64) -> BigRational {
+        BigRational::new(numerator.into(), denominator.into())
+    }
+
+    fn hilbert(n: usize) -> Matrix<BigRational> {
+        let mut matrix = Matrix::zeros(n, n);
+        for (i, row) in matrix.rows_mut().enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                *x = ratio(1, (i + j + 1) as i64);
+            }
+        }
+        matrix
+    }
+
+    #[test]
+    fn rref() {
+        let a = big([[2, 4, 1], [1, 2, 3]]).to_ratio();
+
+        assert_eq!(
+            a.rref(),
+            Matrix::from([
+                [ratio(1, 1), ratio(2, 1), ratio(0, 1)],
+                [ratio(0, 1), ratio(0, 1), ratio(1, 1)],
+            ])
+        );
+    }
+
+    #[test]
+    fn inverse_of_hilbert_matrix() {
+        // The inverse of a Hilbert matrix has integer elements.
+        let inverse = hilbert(4).inverse().unwrap();
+        let expected = big([
+            [16, -120, 240, -140],
+            [-120, 1200, -2700, 1680],
+            [240, -2700, 6480, -4200],
+            [-140, 1680, -4200, 2800],
+        ]);
+
+        assert_eq!(inverse, expected.to_ratio());
+    }
+
+    #[test]
+    fn determinant() {
+        let a = big([[3, 1, 4, 1], [5, 9, 2, 6], [5, 3, 5, 8], [9, 7, 9, 3]]);
+
+        assert_eq!(a.to_ratio().determinant(), a.bareiss_determinant().into());
+        assert_eq!(hilbert(3).determinant(), ratio(1, 2160));
+    }
+
+    #[test]
+    fn rank_and_null_space() {
+        let a = big([[1, 2, 3, 4], [2, 4, 6, 8], [1, 0, 1, 0]]);
+        let basis = a.to_ratio().null_space();
+
+        assert_eq!(a.to_ratio().rank(), a.integer_rank());
+        assert_eq!(basis.len(), a.integer_null_space().len());
+        for (vector, integer_vector) in basis.iter().zip(a.integer_null_space()) {
+            // Both bases are normalized at the same free variables.
+            let scale = vector.iter().find(|x| **x != ratio(0, 1)).unwrap().clone()
+                / BigRational::from_integer(
+                    integer_vector
+                        .iter()
+                        .find(|x| **x != 0.into())
+                        .unwrap()
+                        .clone(),
+                );
+            for (x, y) in vector.iter().zip(integer_vector) {
+                assert_eq!(x.clone(), BigRational::from_integer(y) * scale.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,687 @@
+use super::{Layout, Matrix, Storage};
+use num_traits::{Float, One, Zero};
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+/// The direction along which a [`Matrix`] is reduced.
+///
+/// A reduction along `Axis::Rows` reduces each row to a single value, so
+/// it produces one value per row; a reduction along `Axis::Columns`
+/// produces one value per column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// Reduce each row, producing `num_rows` values.
+    Rows,
+    /// Reduce each column, producing `num_columns` values.
+    Columns,
+}
+
+/// Number of contiguous elements folded by each task of a whole-matrix
+/// reduction.
+///
+/// The partial results are combined in order, so the result depends on
+/// this constant, but not on the number of threads.
+const CHUNK_LEN: usize = 1 << 14;
+
+/// Number of results computed by each task of a reduction across lanes.
+const BLOCK_LEN: usize = 1 << 8;
+
+impl<T, S> Matrix<T, S>
+where
+    T: Clone + Send + Sync,
+    S: Storage<T>,
+{
+    /// Returns the sum of all elements of the matrix, or zero if it is
+    /// empty.
+    pub fn sum(&self) -> T
+    where
+        T: Zero,
+    {
+        self.reduce(T::zero(), |a, x| *a = a.clone() + x.clone(), add)
+    }
+
+    /// Returns the sum of each row or column of the matrix.
+    pub fn sum_axis(&self, axis: Axis) -> Vec<T>
+    where
+        T: Zero,
+    {
+        self.reduce_axis(axis, T::zero(), |a, _, x| *a = a.clone() + x.clone())
+    }
+
+    /// Returns the product of all elements of the matrix, or one if it is
+    /// empty.
+    pub fn product(&self) -> T
+    where
+        T: One,
+    {
+        self.reduce(
+            T::one(),
+            |a, x| *a = a.clone() * x.clone(),
+            |a, b| *a = a.clone() * b,
+        )
+    }
+
+    /// Returns the product of each row or column of the matrix.
+    pub fn product_axis(&self, axis: Axis) -> Vec<T>
+    where
+        T: One,
+    {
+        self.reduce_axis(axis, T::one(), |a, _, x| *a = a.clone() * x.clone())
+    }
+
+    /// Returns the smallest element of the matrix, or `None` if it is
+    /// empty.
+    ///
+    /// If the matrix contains values that are not comparable to themselves,
+    /// such as NaN, the first of them is returned.
+    pub fn min(&self) -> Option<T>
+    where
+        T: PartialOrd,
+    {
+        self.extreme(Ordering::Less, false).map(|(_, x)| x)
+    }
+
+    /// Returns the largest element of the matrix, or `None` if it is
+    /// empty. NaN is handled as in [`min`].
+    ///
+    /// [`min`]: Matrix::min
+    pub fn max(&self) -> Option<T>
+    where
+        T: PartialOrd,
+    {
+        self.extreme(Ordering::Greater, false).map(|(_, x)| x)
+    }
+
+    /// Returns the row and column of the smallest element of the matrix,
+    /// or `None` if it is empty.
+    ///
+    /// Ties are broken in favour of the first element in row-major order.
+    /// NaN is handled as in [`min`].
+    ///
+    /// [`min`]: Matrix::min
+    pub fn argmin(&self) -> Option<(usize, usize)>
+    where
+        T: PartialOrd,
+    {
+        self.extreme(Ordering::Less, false).map(|(index, _)| index)
+    }
+
+    /// Returns the row and column of the largest element of the matrix, or
+    /// `None` if it is empty. Ties and NaN are handled as in [`argmin`].
+    ///
+    /// [`argmin`]: Matrix::argmin
+    pub fn argmax(&self) -> Option<(usize, usize)>
+    where
+        T: PartialOrd,
+    {
+        self.extreme(Ordering::Greater, false)
+            .map(|(index, _)| index)
+    }
+
+    /// Returns the smallest element of each row or column of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows or columns are empty.
+    #[track_caller]
+    pub fn min_axis(&self, axis: Axis) -> Vec<T>
+    where
+        T: PartialOrd,
+    {
+        let extremes = self.extreme_axis(axis, Ordering::Less, false);
+        extremes.into_iter().map(|e| e.unwrap().1).collect()
+    }
+
+    /// Returns the largest element of each row or column of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows or columns are empty.
+    #[track_caller]
+    pub fn max_axis(&self, axis: Axis) -> Vec<T>
+    where
+        T: PartialOrd,
+    {
+        let extremes = self.extreme_axis(axis, Ordering::Greater, false);
+        extremes.into_iter().map(|e| e.unwrap().1).collect()
+    }
+
+    /// Returns the position of the smallest element within each row or
+    /// column of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows or columns are empty.
+    #[track_caller]
+    pub fn argmin_axis(&self, axis: Axis) -> Vec<usize>
+    where
+        T: PartialOrd,
+    {
+        let extremes = self.extreme_axis(axis, Ordering::Less, false);
+        extremes.into_iter().map(|e| e.unwrap().0).collect()
+    }
+
+    /// Returns the position of the largest element within each row or
+    /// column of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows or columns are empty.
+    #[track_caller]
+    pub fn argmax_axis(&self, axis: Axis) -> Vec<usize>
+    where
+        T: PartialOrd,
+    {
+        let extremes = self.extreme_axis(axis, Ordering::Greater, false);
+        extremes.into_iter().map(|e| e.unwrap().0).collect()
+    }
+
+    /// Returns the first extreme element of the matrix in row-major order,
+    /// with its row and column.
+    fn extreme(&self, wanted: Ordering, skip_nan: bool) -> Option<((usize, usize), T)>
+    where
+        T: PartialOrd,
+    {
+        let mut best: Option<((usize, usize), T)> = None;
+        let rows = self.reduce_axis(Axis::Rows, None, |a, j, x| {
+            update_extreme(a, j, x, wanted, skip_nan)
+        });
+        for (i, row) in rows.into_iter().enumerate() {
+            if let Some((j, x)) = row {
+                if replaces(&x, best.as_ref().map(|(_, b)| b), wanted, skip_nan) {
+                    best = Some(((i, j), x));
+                }
+            }
+        }
+        best
+    }
+
+    /// Returns the first extreme element of each row or column, with its
+    /// position within it, or `None` for rows or columns made only of NaN
+    /// if `skip_nan`.
+    #[track_caller]
+    fn extreme_axis(&self, axis: Axis, wanted: Ordering, skip_nan: bool) -> Vec<Option<(usize, T)>>
+    where
+        T: PartialOrd,
+    {
+        self.assert_reducible(axis);
+        self.reduce_axis(axis, None, |a, index, x| {
+            update_extreme(a, index, x, wanted, skip_nan)
+        })
+    }
+
+    /// Folds the elements of the matrix into `init` with `fold`, in chunks
+    /// of [`CHUNK_LEN`] elements in parallel, and then combines the partial
+    /// results of the chunks, in order, with `combine`.
+    fn reduce<A, F, C>(&self, init: A, fold: F, combine: C) -> A
+    where
+        A: Clone + Send + Sync,
+        F: Fn(&mut A, &T) + Sync,
+        C: Fn(&mut A, A),
+    {
+        let fold_chunk = |values: &[T]| {
+            let mut a = init.clone();
+            values.iter().for_each(|x| fold(&mut a, x));
+            a
+        };
+        let partials: Vec<A> = match self.try_as_flattened() {
+            Ok(values) => values.par_chunks(CHUNK_LEN).map(fold_chunk).collect(),
+            Err(_) => {
+                let lanes: Vec<&[T]> = self.lanes().collect();
+                lanes
+                    .into_par_iter()
+                    .flat_map(|lane| lane.par_chunks(CHUNK_LEN).map(fold_chunk))
+                    .collect()
+            }
+        };
+        partials.into_iter().fold(init, |mut a, partial| {
+            combine(&mut a, partial);
+            a
+        })
+    }
+
+    /// Folds each row or column of the matrix into `init` with `fold`,
+    /// which also receives the position of each element within its row or
+    /// column.
+    ///
+    /// Each result is folded sequentially, in order, and the results are
+    /// computed in parallel.
+    fn reduce_axis<A, F>(&self, axis: Axis, init: A, fold: F) -> Vec<A>
+    where
+        A: Clone + Send + Sync,
+        F: Fn(&mut A, usize, &T) + Sync,
+    {
+        let lanes: Vec<&[T]> = self.lanes().collect();
+        let along_lanes = matches!(
+            (axis, self.layout),
+            (Axis::Rows, Layout::RowMajor) | (Axis::Columns, Layout::ColumnMajor)
+        );
+        if along_lanes {
+            lanes
+                .into_par_iter()
+                .map(|lane| {
+                    let mut a = init.clone();
+                    lane.iter()
+                        .enumerate()
+                        .for_each(|(index, x)| fold(&mut a, index, x));
+                    a
+                })
+                .collect()
+        } else {
+            let mut results = vec![init; self.lane_len()];
+            results
+                .par_chunks_mut(BLOCK_LEN)
+                .enumerate()
+                .for_each(|(b, block)| {
+                    for (index, lane) in lanes.iter().enumerate() {
+                        block
+                            .iter_mut()
+                            .zip(&lane[b * BLOCK_LEN..])
+                            .for_each(|(a, x)| fold(a, index, x));
+                    }
+                });
+            results
+        }
+    }
+
+    #[track_caller]
+    fn assert_reducible(&self, axis: Axis) {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(name: &str) -> ! {
+            panic!("`{name}` (is 0) should be > 0");
+        }
+
+        match axis {
+            Axis::Rows if self.num_columns == 0 && self.num_rows > 0 => {
+                assert_failed("num_columns")
+            }
+            Axis::Columns if self.num_rows == 0 && self.num_columns > 0 => {
+                assert_failed("num_rows")
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    T: Float + Send + Sync,
+    S: Storage<T>,
+{
+    /// Returns the mean of all elements of the matrix, or NaN if it is
+    /// empty.
+    pub fn mean(&self) -> T {
+        self.sum() / count(self.num_rows * self.num_columns)
+    }
+
+    /// Returns the mean of each row or column of the matrix.
+    pub fn mean_axis(&self, axis: Axis) -> Vec<T> {
+        let n = count(self.reduced_len(axis));
+        self.sum_axis(axis).into_iter().map(|x| x / n).collect()
+    }
+
+    /// Returns the population variance of all elements of the matrix, that
+    /// is, the mean of the squared deviations from their mean, or NaN if it
+    /// is empty.
+    pub fn variance(&self) -> T {
+        self.reduce(Welford::default(), Welford::push, Welford::merge)
+            .variance()
+    }
+
+    /// Returns the population variance of each row or column of the
+    /// matrix.
+    pub fn variance_axis(&self, axis: Axis) -> Vec<T> {
+        let accumulators = self.reduce_axis(axis, Welford::default(), |a, _, &x| a.push(&x));
+        accumulators.iter().map(Welford::variance).collect()
+    }
+
+    /// Returns the population standard deviation of all elements of the
+    /// matrix, the square root of its [`variance`].
+    ///
+    /// [`variance`]: Matrix::variance
+    pub fn std(&self) -> T {
+        self.variance().sqrt()
+    }
+
+    /// Returns the population standard deviation of each row or column of
+    /// the matrix.
+    pub fn std_axis(&self, axis: Axis) -> Vec<T> {
+        let variances = self.variance_axis(axis);
+        variances.into_iter().map(T::sqrt).collect()
+    }
+
+    /// Returns the sum of all elements of the matrix that are not NaN.
+    pub fn nansum(&self) -> T {
+        self.reduce(T::zero(), |a, &x| *a = nan_add(*a, x), add)
+    }
+
+    /// Returns the sum of the elements that are not NaN in each row or
+    /// column of the matrix.
+    pub fn nansum_axis(&self, axis: Axis) -> Vec<T> {
+        self.reduce_axis(axis, T::zero(), |a, _, &x| *a = nan_add(*a, x))
+    }
+
+    /// Returns the mean of all elements of the matrix that are not NaN, or
+    /// NaN if there are none.
+    pub fn nanmean(&self) -> T {
+        let fold = |(sum, n): &mut (T, usize), &x: &T| {
+            if !x.is_nan() {
+                *sum = *sum + x;
+                *n += 1;
+            }
+        };
+        let combine = |(sum, n): &mut (T, usize), (other_sum, other_n)| {
+            *sum = *sum + other_sum;
+            *n += other_n;
+        };
+        let (sum, n) = self.reduce((T::zero(), 0), fold, combine);
+        sum / count(n)
+    }
+
+    /// Returns the mean of the elements that are not NaN in each row or
+    /// column of the matrix.
+    pub fn nanmean_axis(&self, axis: Axis) -> Vec<T> {
+        let sums = self.reduce_axis(axis, (T::zero(), 0), |(sum, n), _, &x| {
+            if !x.is_nan() {
+                *sum = *sum + x;
+                *n += 1;
+            }
+        });
+        sums.into_iter().map(|(sum, n)| sum / count(n)).collect()
+    }
+
+    /// Returns the smallest element of the matrix that is not NaN, or
+    /// `None` if there is none.
+    pub fn nanmin(&self) -> Option<T> {
+        self.extreme(Ordering::Less, true).map(|(_, x)| x)
+    }
+
+    /// Returns the largest element of the matrix that is not NaN, or `None`
+    /// if there is none.
+    pub fn nanmax(&self) -> Option<T> {
+        self.extreme(Ordering::Greater, true).map(|(_, x)| x)
+    }
+
+    /// Returns the smallest element that is not NaN in each row or column
+    /// of the matrix, or NaN for rows or columns made only of NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows or columns are empty.
+    #[track_caller]
+    pub fn nanmin_axis(&self, axis: Axis) -> Vec<T> {
+        let extremes = self.extreme_axis(axis, Ordering::Less, true);
+        extremes
+            .into_iter()
+            .map(|e| e.map_or(T::nan(), |(_, x)| x))
+            .collect()
+    }
+
+    /// Returns the largest element that is not NaN in each row or column of
+    /// the matrix, or NaN for rows or columns made only of NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows or columns are empty.
+    #[track_caller]
+    pub fn nanmax_axis(&self, axis: Axis) -> Vec<T> {
+        let extremes = self.extreme_axis(axis, Ordering::Greater, true);
+        extremes
+            .into_iter()
+            .map(|e| e.map_or(T::nan(), |(_, x)| x))
+            .collect()
+    }
+
+    /// Returns the number of elements reduced into each result along
+    /// `axis`.
+    fn reduced_len(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::Rows => self.num_columns,
+            Axis::Columns => self.num_rows,
+        }
+    }
+}
+
+impl<S> Matrix<bool, S>
+where
+    S: Storage<bool>,
+{
+    /// Returns `true` if any element of the matrix is `true`.
+    pub fn any(&self) -> bool {
+        self.reduce(false, |a, &x| *a |= x, |a, b| *a |= b)
+    }
+
+    /// Returns `true` if every element of the matrix is `true`, or if it is
+    /// empty.
+    pub fn all(&self) -> bool {
+        self.reduce(true, |a, &x| *a &= x, |a, b| *a &= b)
+    }
+
+    /// Returns, for each row or column of the matrix, whether any of its
+    /// elements is `true`.
+    pub fn any_axis(&self, axis: Axis) -> Vec<bool> {
+        self.reduce_axis(axis, false, |a, _, &x| *a |= x)
+    }
+
+    /// Returns, for each row or column of the matrix, whether all of its
+    /// elements are `true`.
+    pub fn all_axis(&self, axis: Axis) -> Vec<bool> {
+        self.reduce_axis(axis, true, |a, _, &x| *a &= x)
+    }
+}
+
+/// A running count, mean and sum of squared deviations from the mean, as
+/// in Welford's online algorithm for the variance.
+#[derive(Clone, Copy)]
+struct Welford<T> {
+    n: usize,
+    mean: T,
+    m2: T,
+}
+
+impl<T: Float> Default for Welford<T> {
+    fn default() -> Self {
+        Self {
+            n: 0,
+            mean: T::zero(),
+            m2: T::zero(),
+        }
+    }
+}
+
+impl<T: Float> Welford<T> {
+    fn push(&mut self, &x: &T) {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean = self.mean + delta / count(self.n);
+        self.m2 = self.m2 + delta * (x - self.mean);
+    }
+
+    /// Merges the statistics of another set of values into these, as in
+    /// Chan et al.'s parallel algorithm.
+    fn merge(&mut self, other: Self) {
+        if other.n == 0 {
+            return;
+        }
+        let n = self.n + other.n;
+        let delta = other.mean - self.mean;
+        let weight = count::<T>(other.n) / count(n);
+        self.mean = self.mean + delta * weight;
+        self.m2 = self.m2 + other.m2 + delta * delta * count(self.n) * weight;
+        self.n = n;
+    }
+
+    fn variance(&self) -> T {
+        self.m2 / count(self.n)
+    }
+}
+
+/// Returns `true` if `x` should replace `best` as the extreme value, where
+/// `wanted` is `Less` for minima and `Greater` for maxima.
+///
+/// Values that are not comparable to themselves, such as NaN, are skipped
+/// if `skip_nan`, and otherwise win over any other value.
+fn replaces<T: PartialOrd>(x: &T, best: Option<&T>, wanted: Ordering, skip_nan: bool) -> bool {
+    if is_nan(x) {
+        return !skip_nan && best.is_none_or(|b| !is_nan(b));
+    }
+    best.is_none_or(|b| x.partial_cmp(b) == Some(wanted))
+}
+
+#[inline]
+fn update_extreme<T>(
+    best: &mut Option<(usize, T)>,
+    index: usize,
+    x: &T,
+    wanted: Ordering,
+    skip_nan: bool,
+) where
+    T: Clone + PartialOrd,
+{
+    if replaces(x, best.as_ref().map(|(_, b)| b), wanted, skip_nan) {
+        *best = Some((index, x.clone()));
+    }
+}
+
+#[inline]
+fn is_nan<T: PartialOrd>(x: &T) -> bool {
+    x.partial_cmp(x).is_none()
+}
+
+#[inline]
+fn add<T: Clone + Zero>(a: &mut T, b: T) {
+    *a = a.clone() + b;
+}
+
+#[inline]
+fn nan_add<T: Float>(sum: T, x: T) -> T {
+    if x.is_nan() {
+        sum
+    } else {
+        sum + x
+    }
+}
+
+#[inline]
+fn count<T: Float>(n: usize) -> T {
+    T::from(n).unwrap()
+}
+
+#[cfg(test)]
+mod test_reduce {
+    use crate::{Axis, Layout, Matrix};
+
+    #[test]
+    fn sum_and_product() {
+        let a = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+        let b = a.to_layout(Layout::ColumnMajor);
+
+        for m in [&a, &b] {
+            assert_eq!(m.sum(), 21);
+            assert_eq!(m.product(), 720);
+            assert_eq!(m.sum_axis(Axis::Rows), [6, 15]);
+            assert_eq!(m.sum_axis(Axis::Columns), [5, 7, 9]);
+            assert_eq!(m.product_axis(Axis::Columns), [4, 10, 18]);
+        }
+
+        let c = Matrix::with_padding(2, 2, 3, 1);
+        assert_eq!(c.sum(), 4);
+        assert_eq!(
+            Matrix::<i32>::zeros(0, 3).sum_axis(Axis::Columns),
+            [0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn deterministic() {
+        let a: Matrix<f32> = Matrix::random_uniform(300, 300, -1., 1., 0);
+        let single = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+
+        let (sum, mean, var) =
+            single.install(|| (a.sum(), a.mean_axis(Axis::Columns), a.variance()));
+        assert_eq!(sum.to_bits(), a.sum().to_bits());
+        assert_eq!(mean, a.mean_axis(Axis::Columns));
+        assert_eq!(var.to_bits(), a.variance().to_bits());
+    }
+
+    #[test]
+    fn statistics() {
+        let a: Matrix<f64> = Matrix::from([[1., 2., 3.], [5., 7., 9.]]);
+
+        assert_eq!(a.mean(), 4.5);
+        assert_eq!(a.mean_axis(Axis::Rows), [2., 7.]);
+        assert_eq!(a.variance_axis(Axis::Rows), [2. / 3., 8. / 3.]);
+        assert_eq!(a.std_axis(Axis::Columns), [2., 2.5, 3.]);
+        assert!((a.variance() - 95. / 12.).abs() < 1e-12);
+        assert!((a.std() - (95f64 / 12.).sqrt()).abs() < 1e-12);
+        assert!(Matrix::<f64>::zeros(0, 0).mean().is_nan());
+    }
+
+    #[test]
+    fn extremes() {
+        let a = Matrix::from([[3, 1, 4], [1, 5, 9], [2, 6, 5]]);
+        let b = a.to_layout(Layout::ColumnMajor);
+
+        for m in [&a, &b] {
+            assert_eq!(m.min(), Some(1));
+            assert_eq!(m.max(), Some(9));
+            assert_eq!(m.argmin(), Some((0, 1)));
+            assert_eq!(m.argmax(), Some((1, 2)));
+            assert_eq!(m.min_axis(Axis::Rows), [1, 1, 2]);
+            assert_eq!(m.max_axis(Axis::Columns), [3, 6, 9]);
+            assert_eq!(m.argmin_axis(Axis::Columns), [1, 0, 0]);
+            assert_eq!(m.argmax_axis(Axis::Rows), [2, 2, 1]);
+        }
+        assert_eq!(Matrix::<i32>::zeros(0, 2).min(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "`num_columns` (is 0) should be > 0")]
+    fn min_axis_of_empty_rows() {
+        Matrix::<i32>::zeros(2, 0).min_axis(Axis::Rows);
+    }
+
+    #[test]
+    fn nan() {
+        let nan = f64::NAN;
+        let a = Matrix::from([[1., nan, 3.], [nan, nan, -2.]]);
+
+        assert!(a.sum().is_nan());
+        assert!(a.min().unwrap().is_nan());
+        assert_eq!(a.argmax(), Some((0, 1)));
+        assert_eq!(a.nansum(), 2.);
+        assert_eq!(a.nanmean(), 2. / 3.);
+        assert_eq!(a.nanmin(), Some(-2.));
+        assert_eq!(a.nanmax(), Some(3.));
+        assert_eq!(a.nansum_axis(Axis::Rows), [4., -2.]);
+        assert_eq!(a.nanmean_axis(Axis::Rows), [2., -2.]);
+        assert_eq!(a.nanmax_axis(Axis::Rows), [3., -2.]);
+
+        let columns = a.nanmin_axis(Axis::Columns);
+        assert_eq!(columns[0], 1.);
+        assert!(columns[1].is_nan());
+        assert_eq!(Matrix::from([[nan]]).nanmax(), None);
+    }
+
+    #[test]
+    fn any_and_all() {
+        let a = Matrix::from([[true, false], [false, false]]);
+
+        assert!(a.any());
+        assert!(!a.all());
+        assert_eq!(a.any_axis(Axis::Rows), [true, false]);
+        assert_eq!(a.all_axis(Axis::Columns), [false, false]);
+        assert!(Matrix::<bool>::new(0, 3).all());
+        assert_eq!(
+            Matrix::from([[1, 2], [3, 4]])
+                .map(|&x| x > 1)
+                .all_axis(Axis::Rows),
+            [false, true]
+        );
+    }
+}
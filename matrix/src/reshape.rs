@@ -0,0 +1,492 @@
+use super::{Layout, Matrix, StorageMut};
+
+impl<T> Matrix<T> {
+    /// Changes the shape of the matrix to `(num_rows, num_columns)`,
+    /// keeping its elements in the order given by its [`layout`].
+    ///
+    /// No element is moved unless the matrix is padded, in which case the
+    /// padding is removed first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_rows * num_columns` is not the number of elements of
+    /// the matrix.
+    ///
+    /// [`layout`]: Matrix::layout
+    #[track_caller]
+    pub fn reshape(&mut self, num_rows: usize, num_columns: usize) {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_failed(num_elements: usize, len: usize) -> ! {
+            panic!(
+                "`num_rows * num_columns` (is {num_elements}) \
+                should be equal to the number of elements (is {len})"
+            );
+        }
+
+        let len = self.num_rows * self.num_columns;
+        if num_rows * num_columns != len {
+            assert_failed(num_rows * num_columns, len);
+        }
+        self.remove_padding();
+        self.set_shape(num_rows, num_columns);
+    }
+
+    /// Resizes the matrix to `(num_rows, num_columns)`.
+    ///
+    /// The elements in the first `num_rows` rows and `num_columns` columns
+    /// keep their positions, the other ones are dropped, and the new ones
+    /// are set to `fill_value`.
+    pub fn resize(&mut self, num_rows: usize, num_columns: usize, fill_value: T)
+    where
+        T: Clone,
+    {
+        let (num_lanes, lane_len) = match self.layout {
+            Layout::RowMajor => (num_rows, num_columns),
+            Layout::ColumnMajor => (num_columns, num_rows),
+        };
+        let (old_lane_len, stride) = (self.lane_len(), self.leading_dimension);
+        let mut items = std::mem::take(&mut self.data).into_iter();
+        let mut data = Vec::with_capacity(num_lanes * lane_len);
+        for _ in 0..num_lanes.min(self.num_lanes()) {
+            let mut lane = items.by_ref().take(stride);
+            data.extend(lane.by_ref().take(lane_len.min(old_lane_len)));
+            lane.for_each(drop);
+            data.resize(
+                data.len() + lane_len.saturating_sub(old_lane_len),
+                fill_value.clone(),
+            );
+        }
+        data.resize(num_lanes * lane_len, fill_value);
+        self.data = data;
+        self.set_shape(num_rows, num_columns);
+    }
+
+    /// Appends `row` to the bottom of the matrix.
+    ///
+    /// If the matrix has neither rows nor columns, its number of columns
+    /// becomes `row.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix has rows or columns and
+    /// `row.len() != self.num_columns()`.
+    #[track_caller]
+    pub fn push_row(&mut self, row: &[T])
+    where
+        T: Clone,
+    {
+        self.insert_row(self.num_rows, row);
+    }
+
+    /// Inserts `row` at position `index` of the matrix, shifting the rows
+    /// after it down.
+    ///
+    /// If the matrix has neither rows nor columns, its number of columns
+    /// becomes `row.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > self.num_rows()`, or if the matrix has rows or
+    /// columns and `row.len() != self.num_columns()`.
+    #[track_caller]
+    pub fn insert_row(&mut self, index: usize, row: &[T])
+    where
+        T: Clone,
+    {
+        assert_insertion_index(index, self.num_rows, "num_rows");
+        if self.num_rows == 0 && self.num_columns == 0 {
+            self.num_columns = row.len();
+            self.leading_dimension = self.lane_len();
+        }
+        assert_len(row.len(), self.num_columns, "row", "num_columns");
+
+        self.remove_padding();
+        match self.layout {
+            Layout::RowMajor => self.insert_lane(index, row),
+            Layout::ColumnMajor => self.insert_across(index, row),
+        }
+        self.set_shape(self.num_rows + 1, self.num_columns);
+    }
+
+    /// Removes the row at position `index` of the matrix and returns it,
+    /// shifting the rows after it up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.num_rows()`.
+    #[track_caller]
+    pub fn remove_row(&mut self, index: usize) -> Vec<T> {
+        assert_index(index, self.num_rows, "num_rows");
+
+        self.remove_padding();
+        let row = match self.layout {
+            Layout::RowMajor => self.remove_lane(index),
+            Layout::ColumnMajor => self.remove_across(index),
+        };
+        self.set_shape(self.num_rows - 1, self.num_columns);
+        row
+    }
+
+    /// Appends `column` to the right of the matrix.
+    ///
+    /// If the matrix has neither rows nor columns, its number of rows
+    /// becomes `column.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix has rows or columns and
+    /// `column.len() != self.num_rows()`.
+    #[track_caller]
+    pub fn push_column(&mut self, column: &[T])
+    where
+        T: Clone,
+    {
+        self.insert_column(self.num_columns, column);
+    }
+
+    /// Inserts `column` at position `index` of the matrix, shifting the
+    /// columns after it to the right.
+    ///
+    /// If the matrix has neither rows nor columns, its number of rows
+    /// becomes `column.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > self.num_columns()`, or if the matrix has rows
+    /// or columns and `column.len() != self.num_rows()`.
+    #[track_caller]
+    pub fn insert_column(&mut self, index: usize, column: &[T])
+    where
+        T: Clone,
+    {
+        assert_insertion_index(index, self.num_columns, "num_columns");
+        if self.num_rows == 0 && self.num_columns == 0 {
+            self.num_rows = column.len();
+            self.leading_dimension = self.lane_len();
+        }
+        assert_len(column.len(), self.num_rows, "column", "num_rows");
+
+        self.remove_padding();
+        match self.layout {
+            Layout::RowMajor => self.insert_across(index, column),
+            Layout::ColumnMajor => self.insert_lane(index, column),
+        }
+        self.set_shape(self.num_rows, self.num_columns + 1);
+    }
+
+    /// Removes the column at position `index` of the matrix and returns
+    /// it, shifting the columns after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.num_columns()`.
+    #[track_caller]
+    pub fn remove_column(&mut self, index: usize) -> Vec<T> {
+        assert_index(index, self.num_columns, "num_columns");
+
+        self.remove_padding();
+        let column = match self.layout {
+            Layout::RowMajor => self.remove_across(index),
+            Layout::ColumnMajor => self.remove_lane(index),
+        };
+        self.set_shape(self.num_rows, self.num_columns - 1);
+        column
+    }
+
+    /// Sets the shape of the matrix, whose storage already holds the
+    /// elements of that shape without padding.
+    fn set_shape(&mut self, num_rows: usize, num_columns: usize) {
+        self.num_rows = num_rows;
+        self.num_columns = num_columns;
+        self.leading_dimension = self.lane_len();
+    }
+
+    /// Moves the elements of the matrix so that no padding separates its
+    /// lanes.
+    fn remove_padding(&mut self) {
+        if self.is_padded() {
+            let layout = self.layout;
+            let matrix = std::mem::replace(self, Matrix::from_row_major(0, 0, Vec::new()));
+            *self = matrix.into_layout(layout);
+        }
+    }
+
+    /// Inserts `lane` as the lane `index` of the unpadded storage.
+    fn insert_lane(&mut self, index: usize, lane: &[T])
+    where
+        T: Clone,
+    {
+        let start = index * self.lane_len();
+        self.data.splice(start..start, lane.iter().cloned());
+    }
+
+    /// Inserts the elements of `values`, one per lane, at position `index`
+    /// of the lanes of the unpadded storage.
+    fn insert_across(&mut self, index: usize, values: &[T])
+    where
+        T: Clone,
+    {
+        let lane_len = self.lane_len();
+        let mut items = std::mem::take(&mut self.data).into_iter();
+        let mut data = Vec::with_capacity(items.len() + values.len());
+        for value in values {
+            data.extend(items.by_ref().take(index));
+            data.push(value.clone());
+            data.extend(items.by_ref().take(lane_len - index));
+        }
+        self.data = data;
+    }
+
+    /// Removes the lane `index` of the unpadded storage and returns it.
+    fn remove_lane(&mut self, index: usize) -> Vec<T> {
+        let lane_len = self.lane_len();
+        self.data
+            .drain(index * lane_len..(index + 1) * lane_len)
+            .collect()
+    }
+
+    /// Removes the element at position `index` of each lane of the
+    /// unpadded storage and returns them.
+    fn remove_across(&mut self, index: usize) -> Vec<T> {
+        let lane_len = self.lane_len();
+        let mut items = std::mem::take(&mut self.data).into_iter();
+        let mut data = Vec::with_capacity(items.len());
+        let mut removed = Vec::with_capacity(self.num_lanes());
+        for _ in 0..self.num_lanes() {
+            data.extend(items.by_ref().take(index));
+            removed.extend(items.next());
+            data.extend(items.by_ref().take(lane_len - index - 1));
+        }
+        self.data = data;
+        removed
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: StorageMut<T>,
+{
+    /// Swaps the rows `i` and `k` of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `k` is out of bounds.
+    #[track_caller]
+    pub fn swap_rows(&mut self, i: usize, k: usize) {
+        assert_index(i, self.num_rows, "num_rows");
+        assert_index(k, self.num_rows, "num_rows");
+
+        for j in 0..self.num_columns {
+            let (a, b) = (self.offset(i, j), self.offset(k, j));
+            self.data.as_mut_slice().swap(a, b);
+        }
+    }
+
+    /// Swaps the columns `j` and `l` of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `j` or `l` is out of bounds.
+    #[track_caller]
+    pub fn swap_columns(&mut self, j: usize, l: usize) {
+        assert_index(j, self.num_columns, "num_columns");
+        assert_index(l, self.num_columns, "num_columns");
+
+        for i in 0..self.num_rows {
+            let (a, b) = (self.offset(i, j), self.offset(i, l));
+            self.data.as_mut_slice().swap(a, b);
+        }
+    }
+}
+
+#[inline]
+#[track_caller]
+fn assert_index(index: usize, len: usize, len_name: &str) {
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_failed(index: usize, len: usize, len_name: &str) -> ! {
+        panic!("`index` (is {index}) should be < `{len_name}` (is {len})");
+    }
+
+    if index >= len {
+        assert_failed(index, len, len_name);
+    }
+}
+
+#[inline]
+#[track_caller]
+fn assert_insertion_index(index: usize, len: usize, len_name: &str) {
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_failed(index: usize, len: usize, len_name: &str) -> ! {
+        panic!("`index` (is {index}) should be <= `{len_name}` (is {len})");
+    }
+
+    if index > len {
+        assert_failed(index, len, len_name);
+    }
+}
+
+#[inline]
+#[track_caller]
+fn assert_len(len: usize, expected: usize, name: &str, expected_name: &str) {
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn assert_failed(len: usize, expected: usize, name: &str, expected_name: &str) -> ! {
+        panic!("`{name}.len()` (is {len}) should be equal to `{expected_name}` (is {expected})");
+    }
+
+    if len != expected {
+        assert_failed(len, expected, name, expected_name);
+    }
+}
+
+#[cfg(test)]
+mod test_reshape {
+    use crate::{Layout, Matrix};
+
+    fn layouts() -> [Matrix<i32>; 3] {
+        let matrix = Matrix::from([[0, 1, 2], [3, 4, 5]]);
+        let mut padded = Matrix::with_padding(2, 3, 5, -1);
+        padded
+            .rows_mut()
+            .zip(matrix.rows())
+            .for_each(|(a, b)| a.copy_from_slice(b));
+        [
+            matrix.clone(),
+            matrix.to_layout(Layout::ColumnMajor),
+            padded,
+        ]
+    }
+
+    #[test]
+    fn reshape() {
+        let mut matrix = Matrix::from([[0, 1, 2], [3, 4, 5]]);
+        let data = matrix.as_flattened().as_ptr();
+        matrix.reshape(3, 2);
+
+        assert_eq!(matrix, Matrix::from([[0, 1], [2, 3], [4, 5]]));
+        assert_eq!(matrix.as_flattened().as_ptr(), data);
+
+        let mut matrix = Matrix::from([[0, 1, 2], [3, 4, 5]]).into_layout(Layout::ColumnMajor);
+        matrix.reshape(1, 6);
+        assert_eq!(matrix, Matrix::from([[0, 3, 1, 4, 2, 5]]));
+
+        let mut matrix = layouts()[2].clone();
+        matrix.reshape(6, 1);
+        assert_eq!(matrix, Matrix::from([[0], [1], [2], [3], [4], [5]]));
+    }
+
+    #[test]
+    #[should_panic(expected = "`num_rows * num_columns` (is 4) \
+                    should be equal to the number of elements (is 6)")]
+    fn reshape_with_invalid_shape() {
+        Matrix::from([[0, 1, 2], [3, 4, 5]]).reshape(2, 2);
+    }
+
+    #[test]
+    fn resize() {
+        for mut matrix in layouts() {
+            let layout = matrix.layout();
+            matrix.resize(3, 2, 9);
+
+            assert_eq!(matrix, Matrix::from([[0, 1], [3, 4], [9, 9]]));
+            assert_eq!(matrix.layout(), layout);
+
+            matrix.resize(1, 4, 7);
+            assert_eq!(matrix, Matrix::from([[0, 1, 7, 7]]));
+
+            matrix.resize(0, 4, 7);
+            assert_eq!(matrix.shape(), (0, 4));
+        }
+    }
+
+    #[test]
+    fn rows() {
+        for mut matrix in layouts() {
+            matrix.push_row(&[6, 7, 8]);
+            matrix.insert_row(0, &[9, 9, 9]);
+
+            assert_eq!(
+                matrix,
+                Matrix::from([[9, 9, 9], [0, 1, 2], [3, 4, 5], [6, 7, 8]])
+            );
+            assert_eq!(matrix.remove_row(2), [3, 4, 5]);
+            assert_eq!(matrix, Matrix::from([[9, 9, 9], [0, 1, 2], [6, 7, 8]]));
+        }
+    }
+
+    #[test]
+    fn columns() {
+        for mut matrix in layouts() {
+            matrix.push_column(&[6, 7]);
+            matrix.insert_column(1, &[9, 9]);
+
+            assert_eq!(matrix, Matrix::from([[0, 9, 1, 2, 6], [3, 9, 4, 5, 7]]));
+            assert_eq!(matrix.remove_column(3), [2, 5]);
+            assert_eq!(matrix, Matrix::from([[0, 9, 1, 6], [3, 9, 4, 7]]));
+        }
+    }
+
+    #[test]
+    fn push_to_empty_matrix() {
+        for layout in [Layout::RowMajor, Layout::ColumnMajor] {
+            let mut matrix: Matrix<i32> = Matrix::zeros(0, 0).into_layout(layout);
+            matrix.push_row(&[0, 1]);
+            matrix.push_row(&[2, 3]);
+
+            assert_eq!(matrix, Matrix::from([[0, 1], [2, 3]]));
+
+            let mut matrix: Matrix<i32> = Matrix::zeros(0, 0).into_layout(layout);
+            matrix.push_column(&[0, 2]);
+            matrix.push_column(&[1, 3]);
+
+            assert_eq!(matrix, Matrix::from([[0, 1], [2, 3]]));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "`row.len()` (is 2) should be equal to `num_columns` (is 3)")]
+    fn push_row_with_invalid_len() {
+        Matrix::from([[0, 1, 2]]).push_row(&[3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`row.len()` (is 2) should be equal to `num_columns` (is 3)")]
+    fn insert_row_into_no_rows_with_invalid_len() {
+        Matrix::<i32>::zeros(0, 3).insert_row(0, &[1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`column.len()` (is 2) should be equal to `num_rows` (is 3)")]
+    fn insert_column_into_no_columns_with_invalid_len() {
+        Matrix::<i32>::zeros(3, 0).insert_column(0, &[1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`index` (is 3) should be <= `num_columns` (is 2)")]
+    fn insert_column_out_of_bounds() {
+        Matrix::from([[0, 1]]).insert_column(3, &[2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`index` (is 1) should be < `num_rows` (is 1)")]
+    fn remove_row_out_of_bounds() {
+        Matrix::from([[0, 1]]).remove_row(1);
+    }
+
+    #[test]
+    fn swap() {
+        for mut matrix in layouts() {
+            matrix.swap_rows(0, 1);
+            matrix.swap_columns(0, 2);
+
+            assert_eq!(matrix, Matrix::from([[5, 4, 3], [2, 1, 0]]));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use super::{Matrix, Storage};
+use serde::de::Error as _;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The form of a matrix in binary formats.
+#[derive(Serialize)]
+#[serde(rename = "Matrix", bound = "T: Serialize, S: Storage<T>")]
+struct Compact<'a, T, S> {
+    rows: usize,
+    cols: usize,
+    data: Elements<'a, T, S>,
+}
+
+/// The form of a matrix read from binary formats, before its validation.
+#[derive(Deserialize)]
+#[serde(rename = "Matrix")]
+struct CompactOwned<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+/// The elements of a matrix, in row-major order.
+struct Elements<'a, T, S>(&'a Matrix<T, S>);
+
+/// The elements of a row of a matrix.
+struct Row<'a, T, S>(&'a Matrix<T, S>, usize);
+
+impl<T, S> Serialize for Elements<'_, T, S>
+where
+    T: Serialize,
+    S: Storage<T>,
+{
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        let matrix = self.0;
+        let (num_rows, num_columns) = matrix.shape();
+        // Binary formats need the length up front, which `flat_map` does not
+        // tell.
+        let mut seq = serializer.serialize_seq(Some(num_rows * num_columns))?;
+        for i in 0..num_rows {
+            for j in 0..num_columns {
+                seq.serialize_element(&matrix[(i, j)])?;
+            }
+        }
+        seq.end()
+    }
+}
+
+impl<T, S> Serialize for Row<'_, T, S>
+where
+    T: Serialize,
+    S: Storage<T>,
+{
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        let Self(matrix, i) = *self;
+        serializer.collect_seq((0..matrix.num_columns).map(|j| &matrix[(i, j)]))
+    }
+}
+
+/// Serializes the matrix as nested arrays, one per row, in human-readable
+/// formats such as JSON, and as a `{rows, cols, data}` struct, with the
+/// elements in row-major order, in binary ones.
+///
+/// The layout is not serialized: matrices are deserialized in row-major
+/// order. In human-readable formats, a matrix without rows is
+/// deserialized with no columns either.
+impl<T, S> Serialize for Matrix<T, S>
+where
+    T: Serialize,
+    S: Storage<T>,
+{
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_seq((0..self.num_rows).map(|i| Row(self, i)))
+        } else {
+            Compact {
+                rows: self.num_rows,
+                cols: self.num_columns,
+                data: Elements(self),
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+/// Deserializes the forms written by the [`Serialize`] implementation.
+///
+/// Fails, without panicking, if the rows of the nested arrays have
+/// different lengths, or if `rows * cols` is not the number of elements.
+impl<'de, T> Deserialize<'de> for Matrix<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let rows = Vec::<Vec<T>>::deserialize(deserializer)?;
+            let num_columns = rows.first().map_or(0, Vec::len);
+            if let Some((i, row)) = rows
+                .iter()
+                .enumerate()
+                .find(|(_, row)| row.len() != num_columns)
+            {
+                return Err(D::Error::custom(format_args!(
+                    "row {i} has {} elements, but row 0 has {num_columns}",
+                    row.len()
+                )));
+            }
+            let num_rows = rows.len();
+            let data = rows.into_iter().flatten().collect();
+            Ok(Self::from_row_major(num_rows, num_columns, data))
+        } else {
+            let CompactOwned { rows, cols, data } = CompactOwned::deserialize(deserializer)?;
+            if rows.checked_mul(cols) != Some(data.len()) {
+                return Err(D::Error::custom(format_args!(
+                    "`rows * cols` (is {rows} * {cols}) should be equal to `data.len()` (is {})",
+                    data.len()
+                )));
+            }
+            Ok(Self::from_row_major(rows, cols, data))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_serialize {
+    use crate::{Layout, Matrix};
+
+    #[test]
+    fn json() {
+        let a: Matrix<f64> = Matrix::from([[1., 2.5, -3.], [4., 5., 6.]]);
+        let json = serde_json::to_string(&a).unwrap();
+
+        assert_eq!(json, "[[1.0,2.5,-3.0],[4.0,5.0,6.0]]");
+        assert_eq!(
+            serde_json::to_string(&a.to_layout(Layout::ColumnMajor)).unwrap(),
+            json
+        );
+        assert_eq!(serde_json::from_str::<Matrix<f64>>(&json).unwrap(), a);
+        assert_eq!(
+            serde_json::from_str::<Matrix<i32>>("[[], []]")
+                .unwrap()
+                .shape(),
+            (2, 0)
+        );
+    }
+
+    #[test]
+    fn json_errors() {
+        let error = serde_json::from_str::<Matrix<i32>>("[[1, 2], [3]]").unwrap_err();
+
+        assert_eq!(error.to_string(), "row 1 has 1 elements, but row 0 has 2");
+        assert!(serde_json::from_str::<Matrix<i32>>("[1, 2]").is_err());
+        assert!(serde_json::from_str::<Matrix<u8>>("[[256]]").is_err());
+    }
+
+    #[test]
+    fn bincode() {
+        let a: Matrix<f32> = Matrix::random_normal(5, 3, 0., 1., 0);
+        let b = a.to_layout(Layout::ColumnMajor);
+        let bytes = bincode::serialize(&a).unwrap();
+
+        assert_eq!(bytes.len(), 8 + 8 + 8 + 15 * 4);
+        assert_eq!(bincode::serialize(&b).unwrap(), bytes);
+        assert_eq!(bincode::deserialize::<Matrix<f32>>(&bytes).unwrap(), a);
+    }
+
+    #[test]
+    fn bincode_errors() {
+        let encode = |rows: u64, cols: u64, data: &[u8]| {
+            let mut bytes = [rows, cols, data.len() as u64]
+                .map(u64::to_le_bytes)
+                .concat();
+            bytes.extend_from_slice(data);
+            bytes
+        };
+
+        let error = bincode::deserialize::<Matrix<u8>>(&encode(2, 2, &[1, 2, 3])).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`rows * cols` (is 2 * 2) should be equal to `data.len()` (is 3)"
+        );
+        assert!(bincode::deserialize::<Matrix<u8>>(&encode(u64::MAX, 2, &[])).is_err());
+        assert!(bincode::deserialize::<Matrix<u8>>(&encode(1 << 32, 1 << 32, &[])).is_err());
+
+        // A length of data far larger than the input.
+        let mut huge = encode(1, u64::MAX, &[]);
+        huge[16..].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(bincode::deserialize::<Matrix<u8>>(&huge).is_err());
+    }
+}
@@ -0,0 +1,431 @@
+use super::{Layout, Matrix};
+use num_traits::{One, Zero};
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
+
+/// A two-dimensional array type with a shape known at compile time,
+/// written as `SMatrix<T, R, C>`.
+///
+/// Unlike [`Matrix<T>`], an `SMatrix<T, R, C>` stores its `R` rows of `C`
+/// elements inline, without any heap allocation, and the compiler checks
+/// that the shapes of multiplied matrices are compatible. It is meant for
+/// the many small matrices of geometry code, such as 3-by-3 rotations and
+/// 4-by-4 homogeneous transformations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SMatrix<T, const R: usize, const C: usize> {
+    data: [[T; C]; R],
+}
+
+impl<T, const R: usize, const C: usize> SMatrix<T, R, C> {
+    /// Creates an `SMatrix<T, R, C>` filled with `fill_value`.
+    #[inline]
+    pub fn full(fill_value: T) -> Self
+    where
+        T: Copy,
+    {
+        Self {
+            data: [[fill_value; C]; R],
+        }
+    }
+
+    /// Creates an `SMatrix<T, R, C>` filled with the default value of `T`.
+    #[inline]
+    pub fn new() -> Self
+    where
+        T: Copy + Default,
+    {
+        Self::full(T::default())
+    }
+
+    /// Creates an `SMatrix<T, R, C>` filled with zeros.
+    #[inline]
+    pub fn zeros() -> Self
+    where
+        T: Copy + Zero,
+    {
+        Self::full(T::zero())
+    }
+
+    /// Creates an `SMatrix<T, R, C>` filled with ones.
+    #[inline]
+    pub fn ones() -> Self
+    where
+        T: Copy + One,
+    {
+        Self::full(T::one())
+    }
+
+    /// Returns the number of rows in the matrix.
+    #[inline]
+    pub const fn num_rows(&self) -> usize {
+        R
+    }
+
+    /// Returns the number of columns in the matrix.
+    #[inline]
+    pub const fn num_columns(&self) -> usize {
+        C
+    }
+
+    /// Returns the shape `(num_rows, num_columns)` of the matrix.
+    #[inline]
+    pub const fn shape(&self) -> (usize, usize) {
+        (R, C)
+    }
+
+    /// Extracts a slice containing the matrix flattened to one dimension.
+    #[inline]
+    pub fn as_flattened(&self) -> &[T] {
+        self.data.as_flattened()
+    }
+
+    /// Returns the rows of the matrix as an array of arrays.
+    #[inline]
+    pub const fn as_array(&self) -> &[[T; C]; R] {
+        &self.data
+    }
+
+    /// Returns the transpose of the matrix.
+    pub fn transpose(&self) -> SMatrix<T, C, R>
+    where
+        T: Copy,
+    {
+        SMatrix {
+            data: std::array::from_fn(|j| std::array::from_fn(|i| self.data[i][j])),
+        }
+    }
+}
+
+impl<T, const N: usize> SMatrix<T, N, N>
+where
+    T: Copy + Zero + One,
+{
+    /// Creates the `N`-by-`N` identity matrix.
+    pub fn identity() -> Self {
+        let mut matrix = Self::zeros();
+        (0..N).for_each(|i| matrix.data[i][i] = T::one());
+        matrix
+    }
+}
+
+impl<T, const R: usize, const C: usize> Default for SMatrix<T, R, C>
+where
+    T: Copy + Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const R: usize, const C: usize> From<[[T; C]; R]> for SMatrix<T, R, C> {
+    /// Creates an `SMatrix<T, R, C>` that takes ownership of `array`.
+    #[inline]
+    fn from(array: [[T; C]; R]) -> Self {
+        Self { data: array }
+    }
+}
+
+impl<T, const R: usize, const C: usize> From<SMatrix<T, R, C>> for Matrix<T> {
+    /// Creates a `Matrix<T>` with shape `(R, C)` and moves `matrix`'s items
+    /// into it.
+    #[inline]
+    fn from(matrix: SMatrix<T, R, C>) -> Self {
+        Matrix::from(matrix.data)
+    }
+}
+
+impl<T, const R: usize, const C: usize> TryFrom<Matrix<T>> for SMatrix<T, R, C> {
+    type Error = Matrix<T>;
+
+    /// Moves the items of `matrix` into an `SMatrix<T, R, C>`, or gives
+    /// `matrix` back if its shape is not `(R, C)`.
+    fn try_from(matrix: Matrix<T>) -> Result<Self, Self::Error> {
+        if matrix.shape() != (R, C) {
+            return Err(matrix);
+        }
+        let mut items = matrix.into_layout(Layout::RowMajor).data.into_iter();
+        Ok(Self {
+            data: std::array::from_fn(|_| std::array::from_fn(|_| items.next().unwrap())),
+        })
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<usize> for SMatrix<T, R, C> {
+    type Output = [T; C];
+
+    #[inline]
+    fn index(&self, row_index: usize) -> &Self::Output {
+        &self.data[row_index]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<usize> for SMatrix<T, R, C> {
+    #[inline]
+    fn index_mut(&mut self, row_index: usize) -> &mut Self::Output {
+        &mut self.data[row_index]
+    }
+}
+
+impl<T, const R: usize, const K: usize, const C: usize> Mul<SMatrix<T, K, C>> for SMatrix<T, R, K>
+where
+    T: Copy + Zero + Mul<Output = T>,
+{
+    type Output = SMatrix<T, R, C>;
+
+    /// Multiplies an `R`-by-`K` matrix by a `K`-by-`C` matrix, producing an
+    /// `R`-by-`C` matrix.
+    fn mul(self, rhs: SMatrix<T, K, C>) -> Self::Output {
+        let mut c = SMatrix::zeros();
+        for (ci, ai) in c.data.iter_mut().zip(self.data.iter()) {
+            for (bk, aik) in rhs.data.iter().zip(ai.iter()) {
+                for (cij, bkj) in ci.iter_mut().zip(bk.iter()) {
+                    *cij = *cij + *aik * *bkj;
+                }
+            }
+        }
+        c
+    }
+}
+
+impl<T> SMatrix<T, 2, 2>
+where
+    T: Copy
+        + Zero
+        + PartialEq
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>
+        + Neg<Output = T>,
+{
+    /// Returns the determinant of the matrix.
+    #[inline]
+    pub fn determinant(&self) -> T {
+        let [[a, b], [c, d]] = self.data;
+        a * d - b * c
+    }
+
+    /// Returns the inverse of the matrix, or `None` if its determinant is
+    /// zero.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == T::zero() {
+            return None;
+        }
+        let [[a, b], [c, d]] = self.data;
+        Some(Self::from([
+            [d / determinant, -b / determinant],
+            [-c / determinant, a / determinant],
+        ]))
+    }
+}
+
+impl<T> SMatrix<T, 3, 3>
+where
+    T: Copy
+        + Zero
+        + PartialEq
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>,
+{
+    /// Returns the determinant of the matrix.
+    #[inline]
+    pub fn determinant(&self) -> T {
+        let m = &self.data;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// Returns the inverse of the matrix, or `None` if its determinant is
+    /// zero.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == T::zero() {
+            return None;
+        }
+
+        // The inverse is the adjugate, the transpose of the cofactor
+        // matrix, divided by the determinant.
+        let m = &self.data;
+        let cofactor = |i0: usize, i1: usize, j0: usize, j1: usize| {
+            (m[i0][j0] * m[i1][j1] - m[i0][j1] * m[i1][j0]) / determinant
+        };
+        Some(Self::from([
+            [
+                cofactor(1, 2, 1, 2),
+                cofactor(0, 2, 2, 1),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                cofactor(1, 2, 2, 0),
+                cofactor(0, 2, 0, 2),
+                cofactor(0, 1, 2, 0),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                cofactor(0, 2, 1, 0),
+                cofactor(0, 1, 0, 1),
+            ],
+        ]))
+    }
+}
+
+impl<T> SMatrix<T, 4, 4>
+where
+    T: Copy
+        + Zero
+        + PartialEq
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>
+        + Neg<Output = T>,
+{
+    /// Returns the twelve 2-by-2 minors from which both the determinant and
+    /// the inverse are computed (Laplace expansion by complementary minors).
+    #[inline]
+    fn minors(&self) -> ([T; 6], [T; 6]) {
+        let m = &self.data;
+        let top = [
+            m[0][0] * m[1][1] - m[1][0] * m[0][1],
+            m[0][0] * m[1][2] - m[1][0] * m[0][2],
+            m[0][0] * m[1][3] - m[1][0] * m[0][3],
+            m[0][1] * m[1][2] - m[1][1] * m[0][2],
+            m[0][1] * m[1][3] - m[1][1] * m[0][3],
+            m[0][2] * m[1][3] - m[1][2] * m[0][3],
+        ];
+        let bottom = [
+            m[2][0] * m[3][1] - m[3][0] * m[2][1],
+            m[2][0] * m[3][2] - m[3][0] * m[2][2],
+            m[2][0] * m[3][3] - m[3][0] * m[2][3],
+            m[2][1] * m[3][2] - m[3][1] * m[2][2],
+            m[2][1] * m[3][3] - m[3][1] * m[2][3],
+            m[2][2] * m[3][3] - m[3][2] * m[2][3],
+        ];
+        (top, bottom)
+    }
+
+    /// Returns the determinant of the matrix.
+    #[inline]
+    pub fn determinant(&self) -> T {
+        let ([s0, s1, s2, s3, s4, s5], [c0, c1, c2, c3, c4, c5]) = self.minors();
+        s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0
+    }
+
+    /// Returns the inverse of the matrix, or `None` if its determinant is
+    /// zero.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == T::zero() {
+            return None;
+        }
+
+        let m = &self.data;
+        let ([s0, s1, s2, s3, s4, s5], [c0, c1, c2, c3, c4, c5]) = self.minors();
+        let adjugate = [
+            [
+                m[1][1] * c5 - m[1][2] * c4 + m[1][3] * c3,
+                -m[0][1] * c5 + m[0][2] * c4 - m[0][3] * c3,
+                m[3][1] * s5 - m[3][2] * s4 + m[3][3] * s3,
+                -m[2][1] * s5 + m[2][2] * s4 - m[2][3] * s3,
+            ],
+            [
+                -m[1][0] * c5 + m[1][2] * c2 - m[1][3] * c1,
+                m[0][0] * c5 - m[0][2] * c2 + m[0][3] * c1,
+                -m[3][0] * s5 + m[3][2] * s2 - m[3][3] * s1,
+                m[2][0] * s5 - m[2][2] * s2 + m[2][3] * s1,
+            ],
+            [
+                m[1][0] * c4 - m[1][1] * c2 + m[1][3] * c0,
+                -m[0][0] * c4 + m[0][1] * c2 - m[0][3] * c0,
+                m[3][0] * s4 - m[3][1] * s2 + m[3][3] * s0,
+                -m[2][0] * s4 + m[2][1] * s2 - m[2][3] * s0,
+            ],
+            [
+                -m[1][0] * c3 + m[1][1] * c1 - m[1][2] * c0,
+                m[0][0] * c3 - m[0][1] * c1 + m[0][2] * c0,
+                -m[3][0] * s3 + m[3][1] * s1 - m[3][2] * s0,
+                m[2][0] * s3 - m[2][1] * s1 + m[2][2] * s0,
+            ],
+        ];
+        Some(Self::from(adjugate.map(|row| row.map(|x| x / determinant))))
+    }
+}
+
+#[cfg(test)]
+mod test_smatrix {
+    use super::SMatrix;
+    use crate::{Matrix, ModP};
+
+    #[test]
+    fn shape() {
+        let matrix: SMatrix<f32, 3, 2> = SMatrix::new();
+
+        assert_eq!(matrix.num_rows(), 3);
+        assert_eq!(matrix.num_columns(), 2);
+        assert_eq!(matrix.shape(), (3, 2));
+        assert_eq!(
+            std::mem::size_of_val(&matrix),
+            6 * std::mem::size_of::<f32>()
+        );
+    }
+
+    #[test]
+    fn mul() {
+        let a = SMatrix::from([[0., 1.], [2., 3.], [4., 5.]]);
+        let b = SMatrix::from([[6.], [7.]]);
+
+        assert_eq!(a * b, SMatrix::from([[7.], [33.], [59.]]));
+        assert_eq!(a * SMatrix::identity(), a);
+    }
+
+    #[test]
+    fn transpose() {
+        let a = SMatrix::from([[0, 1, 2], [3, 4, 5]]);
+
+        assert_eq!(a.transpose(), SMatrix::from([[0, 3], [1, 4], [2, 5]]));
+    }
+
+    #[test]
+    fn determinant() {
+        assert_eq!(SMatrix::from([[1, 2], [3, 4]]).determinant(), -2);
+        assert_eq!(
+            SMatrix::from([[1, 2, 3], [0, 1, 4], [5, 6, 0]]).determinant(),
+            1
+        );
+        let a = SMatrix::from([[2, -1, 0, 3], [1, 3, -2, 0], [0, 4, 1, -1], [5, 0, 2, 2]]);
+        assert_eq!(a.determinant(), -119);
+    }
+
+    #[test]
+    fn inverse() {
+        let a = SMatrix::from([[3., 1.], [5., 2.]]);
+        assert_eq!(a * a.inverse().unwrap(), SMatrix::identity());
+
+        let a = SMatrix::from([[1., 2., 3.], [0., 1., 4.], [5., 6., 0.]]);
+        assert_eq!(a * a.inverse().unwrap(), SMatrix::identity());
+
+        let a = SMatrix::from([[2, -1, 0, 3], [1, 3, -2, 0], [0, 4, 1, -1], [5, 0, 2, 2]]);
+        let a = SMatrix::from(a.as_array().map(|row| row.map(ModP::<101>::from_i64)));
+        assert_eq!(a * a.inverse().unwrap(), SMatrix::identity());
+        assert_eq!(a.inverse().unwrap() * a, SMatrix::identity());
+
+        assert_eq!(SMatrix::from([[1., 2.], [2., 4.]]).inverse(), None);
+        assert_eq!(SMatrix::<f64, 3, 3>::zeros().inverse(), None);
+        assert_eq!(SMatrix::<f64, 4, 4>::ones().inverse(), None);
+    }
+
+    #[test]
+    fn conversions() {
+        let a = SMatrix::from([[0., 1.], [2., 3.], [4., 5.]]);
+        let b: Matrix<f64> = a.into();
+
+        assert_eq!(b, Matrix::from([[0., 1.], [2., 3.], [4., 5.]]));
+        assert_eq!(SMatrix::try_from(b.clone()), Ok(a));
+
+        let c: Result<SMatrix<f64, 2, 3>, _> = SMatrix::try_from(b.clone());
+        assert_eq!(c, Err(b));
+    }
+}
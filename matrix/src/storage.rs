@@ -0,0 +1,535 @@
+use super::Matrix;
+use num_traits::Zero;
+use std::alloc;
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+
+/// A contiguous buffer that holds the elements of a [`Matrix`].
+///
+/// `Matrix<T>` owns its elements in a `Vec<T>`, but a `Matrix<T, S>` can
+/// wrap any buffer that implements this trait: a borrowed slice, an
+/// [`AlignedVec<T>`] or a memory-mapped file, for instance. Reading
+/// operations such as [`rows`], indexing and [`Matrix::mul`] work for any
+/// storage.
+///
+/// [`rows`]: Matrix::rows
+pub trait Storage<T> {
+    /// Extracts a slice containing all the elements of the buffer.
+    fn as_slice(&self) -> &[T];
+}
+
+/// A [`Storage`] whose elements can be modified in place.
+pub trait StorageMut<T>: Storage<T> {
+    /// Extracts a mutable slice containing all the elements of the buffer.
+    fn as_mut_slice(&mut self) -> &mut [T];
+}
+
+impl<T> Storage<T> for Vec<T> {
+    #[inline]
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> StorageMut<T> for Vec<T> {
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> Storage<T> for Box<[T]> {
+    #[inline]
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> StorageMut<T> for Box<[T]> {
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> Storage<T> for &[T] {
+    #[inline]
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> Storage<T> for &mut [T] {
+    #[inline]
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> StorageMut<T> for &mut [T] {
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T: Clone> Storage<T> for Cow<'_, [T]> {
+    #[inline]
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+/// Clones the borrowed elements on the first mutable access.
+impl<T: Clone> StorageMut<T> for Cow<'_, [T]> {
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self.to_mut()
+    }
+}
+
+impl<T> Storage<T> for AlignedVec<T> {
+    #[inline]
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> StorageMut<T> for AlignedVec<T> {
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+/// A fixed-length, heap-allocated buffer whose first element is aligned to
+/// [`AlignedVec::ALIGNMENT`] bytes, so that SIMD loads of whole cache lines
+/// never straddle two of them.
+pub struct AlignedVec<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    marker: PhantomData<T>,
+}
+
+// SAFETY: `AlignedVec<T>` owns its elements, like a `Vec<T>`.
+unsafe impl<T: Send> Send for AlignedVec<T> {}
+
+// SAFETY: `AlignedVec<T>` only hands out shared references to its elements
+// through `&self`, like a `Vec<T>`.
+unsafe impl<T: Sync> Sync for AlignedVec<T> {}
+
+impl<T> AlignedVec<T> {
+    /// The alignment, in bytes, of the first element: the size of a cache
+    /// line and of an AVX-512 register.
+    pub const ALIGNMENT: usize = 64;
+
+    /// Creates an `AlignedVec<T>` of length `len` whose element `i` is
+    /// `f(i)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the buffer overflows `isize`.
+    pub fn from_fn<F>(len: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize) -> T,
+    {
+        /// Drops the elements written so far and frees the buffer if `f`
+        /// panics.
+        struct Guard<T> {
+            ptr: NonNull<T>,
+            initialized: usize,
+            layout: alloc::Layout,
+        }
+
+        impl<T> Drop for Guard<T> {
+            fn drop(&mut self) {
+                // SAFETY: the first `initialized` elements were written and
+                // the buffer was allocated with `layout`.
+                unsafe {
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                        self.ptr.as_ptr(),
+                        self.initialized,
+                    ));
+                    deallocate(self.ptr, self.layout);
+                }
+            }
+        }
+
+        let layout = buffer_layout::<T>(len);
+        let mut guard: Guard<T> = Guard {
+            ptr: allocate(layout),
+            initialized: 0,
+            layout,
+        };
+        while guard.initialized < len {
+            // SAFETY: the buffer has room for `len` elements.
+            unsafe {
+                guard
+                    .ptr
+                    .as_ptr()
+                    .add(guard.initialized)
+                    .write(f(guard.initialized))
+            };
+            guard.initialized += 1;
+        }
+        let ptr = guard.ptr;
+        std::mem::forget(guard);
+        Self {
+            ptr,
+            len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates an `AlignedVec<T>` of length `len` filled with `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the buffer overflows `isize`.
+    pub fn from_elem(value: T, len: usize) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(len, |_| value.clone())
+    }
+
+    /// Returns the number of elements in the buffer.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the buffer holds no elements.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Returns the layout of a buffer of `len` elements of type `T` aligned to
+/// [`AlignedVec::ALIGNMENT`] bytes.
+fn buffer_layout<T>(len: usize) -> alloc::Layout {
+    alloc::Layout::array::<T>(len)
+        .and_then(|layout| layout.align_to(AlignedVec::<T>::ALIGNMENT))
+        .expect("capacity overflow")
+}
+
+fn allocate<T>(layout: alloc::Layout) -> NonNull<T> {
+    if layout.size() == 0 {
+        return NonNull::dangling();
+    }
+    // SAFETY: the size of `layout` is not zero.
+    let ptr = unsafe { alloc::alloc(layout) };
+    NonNull::new(ptr.cast()).unwrap_or_else(|| alloc::handle_alloc_error(layout))
+}
+
+/// # Safety
+///
+/// `ptr` must have been returned by `allocate(layout)`.
+unsafe fn deallocate<T>(ptr: NonNull<T>, layout: alloc::Layout) {
+    if layout.size() != 0 {
+        alloc::dealloc(ptr.as_ptr().cast(), layout);
+    }
+}
+
+impl<T> Drop for AlignedVec<T> {
+    fn drop(&mut self) {
+        // SAFETY: the `len` elements were written by `from_fn`, which
+        // allocated the buffer with the same layout.
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.len));
+            deallocate(self.ptr, buffer_layout::<T>(self.len));
+        }
+    }
+}
+
+impl<T> Deref for AlignedVec<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        // SAFETY: the buffer holds `len` initialized elements.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> DerefMut for AlignedVec<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        // SAFETY: the buffer holds `len` initialized elements, and `&mut
+        // self` guarantees exclusive access to them.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Clone> Clone for AlignedVec<T> {
+    fn clone(&self) -> Self {
+        Self::from_fn(self.len, |i| self[i].clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AlignedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for AlignedVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for AlignedVec<T> {}
+
+impl<T> From<Vec<T>> for AlignedVec<T> {
+    /// Creates an `AlignedVec<T>` and moves `vec`'s items into it.
+    fn from(vec: Vec<T>) -> Self {
+        let len = vec.len();
+        let mut items = vec.into_iter();
+        Self::from_fn(len, |_| items.next().unwrap())
+    }
+}
+
+impl<T> FromIterator<T> for AlignedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: Storage<T>,
+{
+    /// Returns a `Matrix<T, &[T]>` that borrows the elements of the matrix.
+    #[inline]
+    pub fn view(&self) -> Matrix<T, &[T]> {
+        Matrix {
+            data: self.data.as_slice(),
+            num_rows: self.num_rows,
+            num_columns: self.num_columns,
+            leading_dimension: self.leading_dimension,
+            layout: self.layout,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a `Matrix<T, &mut [T]>` that mutably borrows the elements of
+    /// the matrix.
+    #[inline]
+    pub fn view_mut(&mut self) -> Matrix<T, &mut [T]>
+    where
+        S: StorageMut<T>,
+    {
+        Matrix {
+            data: self.data.as_mut_slice(),
+            num_rows: self.num_rows,
+            num_columns: self.num_columns,
+            leading_dimension: self.leading_dimension,
+            layout: self.layout,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a `Matrix<T>` that owns a copy of the elements of the matrix,
+    /// in the same layout and without padding.
+    pub fn to_owned_matrix(&self) -> Matrix<T>
+    where
+        T: Clone,
+    {
+        self.to_layout(self.layout)
+    }
+
+    /// Consumes the matrix, returning the buffer that holds its elements.
+    #[inline]
+    pub fn into_storage(self) -> S {
+        self.data
+    }
+}
+
+impl<T> Matrix<T, AlignedVec<T>> {
+    /// Creates a row-major `Matrix<T, AlignedVec<T>>` with shape
+    /// `(num_rows, num_columns)`, filled with `fill_value`, whose rows all
+    /// start on [`AlignedVec::ALIGNMENT`]-byte boundaries.
+    ///
+    /// Unless a row fills a whole number of cache lines, it is followed by
+    /// padding, so that two threads writing to neighbouring rows never
+    /// share a cache line. See [`Matrix::with_padding`].
+    #[track_caller]
+    pub fn full_aligned(num_rows: usize, num_columns: usize, fill_value: T) -> Self
+    where
+        T: Clone,
+    {
+        let leading_dimension = aligned_leading_dimension::<T>(num_columns);
+        let data = AlignedVec::from_elem(fill_value, num_rows * leading_dimension);
+        Self::from_padded(num_rows, num_columns, leading_dimension, data)
+    }
+
+    /// Creates a row-major `Matrix<T, AlignedVec<T>>` with shape
+    /// `(num_rows, num_columns)`, filled with zeros, whose rows all start
+    /// on [`AlignedVec::ALIGNMENT`]-byte boundaries.
+    #[inline]
+    #[track_caller]
+    pub fn zeros_aligned(num_rows: usize, num_columns: usize) -> Self
+    where
+        T: Clone + Zero,
+    {
+        Self::full_aligned(num_rows, num_columns, T::zero())
+    }
+}
+
+/// Returns the smallest leading dimension, not smaller than `num_columns`,
+/// that is a whole number of [`AlignedVec::ALIGNMENT`]-byte blocks.
+fn aligned_leading_dimension<T>(num_columns: usize) -> usize {
+    let size = std::mem::size_of::<T>();
+    if size == 0 {
+        return num_columns;
+    }
+    // The largest power of two that divides `size`, up to the alignment.
+    let factor = (size & size.wrapping_neg()).min(AlignedVec::<T>::ALIGNMENT);
+    let step = AlignedVec::<T>::ALIGNMENT / factor;
+    num_columns.div_ceil(step) * step
+}
+
+impl<T> From<Matrix<T>> for Matrix<T, AlignedVec<T>> {
+    /// Moves the elements of `matrix` into an [`AlignedVec<T>`], keeping
+    /// its shape and layout.
+    fn from(matrix: Matrix<T>) -> Self {
+        Matrix {
+            data: AlignedVec::from(matrix.data),
+            num_rows: matrix.num_rows,
+            num_columns: matrix.num_columns,
+            leading_dimension: matrix.leading_dimension,
+            layout: matrix.layout,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone> From<&Matrix<T, AlignedVec<T>>> for Matrix<T> {
+    /// Copies the elements of `matrix` into a `Matrix<T>` with the same
+    /// shape and layout, without padding.
+    fn from(matrix: &Matrix<T, AlignedVec<T>>) -> Self {
+        matrix.to_owned_matrix()
+    }
+}
+
+#[cfg(test)]
+mod test_storage {
+    use super::{AlignedVec, Storage};
+    use crate::{Layout, Matrix};
+    use std::cell::Cell;
+
+    #[test]
+    fn borrowed_slice() {
+        let buffer = [0., 1., 2., 3., 4., 5.];
+        let matrix = Matrix::from_row_major(3, 2, &buffer[..]);
+
+        assert_eq!(matrix, Matrix::from([[0., 1.], [2., 3.], [4., 5.]]));
+        assert_eq!(matrix.rows().nth(1), Some([2., 3.].as_slice()));
+        assert_eq!(matrix[2][0], 4.);
+        assert_eq!(matrix.to_owned_matrix().as_flattened(), buffer);
+    }
+
+    #[test]
+    fn mutably_borrowed_slice() {
+        let mut buffer = [0; 6];
+        let mut matrix = Matrix::from_column_major(2, 3, &mut buffer[..]);
+        matrix[(1, 0)] = 1;
+        matrix.columns_mut().last().unwrap().fill(2);
+
+        assert_eq!(matrix, Matrix::from([[0, 0, 2], [1, 0, 2]]));
+        assert_eq!(buffer, [0, 1, 0, 0, 2, 2]);
+    }
+
+    #[test]
+    fn views() {
+        let mut matrix = Matrix::from([[0, 1], [2, 3]]);
+        matrix.view_mut().rows_mut().for_each(|row| row.reverse());
+
+        assert_eq!(matrix.view(), Matrix::from([[1, 0], [3, 2]]));
+        assert_eq!(matrix.view().into_storage(), [1, 0, 3, 2]);
+    }
+
+    #[test]
+    fn aligned_vec() {
+        let aligned = AlignedVec::from_fn(100, |i| i as f32);
+
+        assert_eq!(aligned.as_ptr() as usize % AlignedVec::<f32>::ALIGNMENT, 0);
+        assert_eq!(aligned.len(), 100);
+        assert_eq!(aligned[99], 99.);
+        assert_eq!(aligned.clone(), aligned);
+        assert_eq!(AlignedVec::from(vec![1u8, 2, 3]).as_slice(), [1, 2, 3]);
+        assert!(AlignedVec::<f64>::from_fn(0, |_| unreachable!()).is_empty());
+    }
+
+    #[test]
+    fn aligned_vec_drops_elements() {
+        let drops = Cell::new(0);
+        struct Counter<'a>(&'a Cell<usize>);
+        impl Drop for Counter<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        drop(AlignedVec::from_fn(5, |_| Counter(&drops)));
+        assert_eq!(drops.get(), 5);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            AlignedVec::from_fn(5, |i| if i < 3 { Counter(&drops) } else { panic!() })
+        }));
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 8);
+    }
+
+    #[test]
+    fn aligned_matrix() {
+        let a = Matrix::full_aligned(3, 2, 1.);
+        let b: Matrix<f64, AlignedVec<f64>> = Matrix::from([[1., 2.], [3., 4.]]).into();
+        let c = Matrix::mul(&a, &b);
+
+        assert_eq!(a[0].as_ptr() as usize % 64, 0);
+        assert_eq!(c, Matrix::from([[4., 6.], [4., 6.], [4., 6.]]));
+        assert_eq!(Matrix::from(&b), Matrix::from([[1., 2.], [3., 4.]]));
+    }
+
+    #[test]
+    fn zeros_aligned() {
+        let mut matrix: Matrix<f64, _> = Matrix::zeros_aligned(3, 5);
+        matrix[2][4] = 1.;
+
+        assert_eq!(matrix.leading_dimension(), 8);
+        assert!(matrix
+            .rows()
+            .all(|row| (row.as_ptr() as usize).is_multiple_of(AlignedVec::<f64>::ALIGNMENT)));
+        assert_eq!(matrix.rows().last(), Some([0., 0., 0., 0., 1.].as_slice()));
+        assert_eq!(Matrix::full_aligned(1, 1, [0u8; 3]).leading_dimension(), 64);
+    }
+
+    /// A read-only buffer owned by someone else, such as a memory-mapped
+    /// file.
+    struct Mapped<'a>(&'a [f32]);
+
+    impl Storage<f32> for Mapped<'_> {
+        fn as_slice(&self) -> &[f32] {
+            self.0
+        }
+    }
+
+    #[test]
+    fn custom_storage() {
+        let file = [1f32, 2., 3., 4.];
+        let a = Matrix::from_column_major(2, 2, Mapped(&file));
+
+        assert_eq!(a.layout(), Layout::ColumnMajor);
+        assert_eq!(a[(0, 1)], 3.);
+        assert_eq!(
+            Matrix::mul(&a, &Matrix::from([[1f32], [1.]])),
+            Matrix::from([[4.], [6.]])
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use super::exact::assert_square;
+use super::{Matrix, Storage, StorageMut};
+use num_traits::{One, Zero};
+use std::ops::{Add, Div, Mul, Neg};
+
+impl<T> Matrix<T> {
+    /// Creates the `n`-by-`n` identity matrix.
+    #[inline]
+    pub fn identity(n: usize) -> Self
+    where
+        T: Clone + Zero + One,
+    {
+        Self::eye(n, n, 0)
+    }
+
+    /// Creates a `Matrix<T>` with shape `(num_rows, num_columns)` whose
+    /// `k`-th diagonal is filled with ones and all other elements are zero.
+    ///
+    /// The main diagonal is `k = 0`. A positive `k` refers to a diagonal
+    /// above it, and a negative `k` to a diagonal below it.
+    pub fn eye(num_rows: usize, num_columns: usize, k: isize) -> Self
+    where
+        T: Clone + Zero + One,
+    {
+        from_fn(num_rows, num_columns, |i, j| {
+            if j as isize - i as isize == k {
+                T::one()
+            } else {
+                T::zero()
+            }
+        })
+    }
+
+    /// Creates a square `Matrix<T>` with `diagonal` as its main diagonal
+    /// and all other elements zero.
+    pub fn from_diagonal(diagonal: &[T]) -> Self
+    where
+        T: Clone + Zero,
+    {
+        let n = diagonal.len();
+        from_fn(n, n, |i, j| {
+            if i == j {
+                diagonal[i].clone()
+            } else {
+                T::zero()
+            }
+        })
+    }
+
+    /// Creates the Toeplitz matrix with the given first column and first
+    /// row, whose elements are constant along each diagonal.
+    ///
+    /// The matrix has shape `(first_column.len(), first_row.len())`. Its
+    /// element at `(0, 0)` is taken from `first_column`, so the first element
+    /// of `first_row` is ignored.
+    pub fn toeplitz(first_column: &[T], first_row: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        from_fn(first_column.len(), first_row.len(), |i, j| {
+            if i >= j {
+                first_column[i - j].clone()
+            } else {
+                first_row[j - i].clone()
+            }
+        })
+    }
+
+    /// Creates the Hankel matrix with the given first column and last row,
+    /// whose elements are constant along each anti-diagonal.
+    ///
+    /// The matrix has shape `(first_column.len(), last_row.len())`. Its
+    /// element at `(num_rows - 1, 0)` is taken from `first_column`, so the
+    /// first element of `last_row` is ignored.
+    pub fn hankel(first_column: &[T], last_row: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        let num_rows = first_column.len();
+        from_fn(num_rows, last_row.len(), |i, j| {
+            if i + j < num_rows {
+                first_column[i + j].clone()
+            } else {
+                last_row[i + j + 1 - num_rows].clone()
+            }
+        })
+    }
+
+    /// Creates the circulant matrix with `first_column` as its first
+    /// column, in which each column is the previous one rotated one
+    /// element down.
+    pub fn circulant(first_column: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        let n = first_column.len();
+        from_fn(n, n, |i, j| first_column[(n + i - j) % n].clone())
+    }
+
+    /// Creates the Vandermonde matrix with shape `(x.len(), num_columns)`
+    /// whose element at `(i, j)` is `x[i]` raised to the power `j`.
+    ///
+    /// The powers increase from left to right, so the first column is all
+    /// ones.
+    pub fn vandermonde(x: &[T], num_columns: usize) -> Self
+    where
+        T: Clone + One + Mul<Output = T>,
+    {
+        let mut data = Vec::with_capacity(x.len() * num_columns);
+        for xi in x {
+            let mut power = T::one();
+            for _ in 0..num_columns {
+                data.push(power.clone());
+                power = power * xi.clone();
+            }
+        }
+        Self::from_row_major(x.len(), num_columns, data)
+    }
+
+    /// Creates the `n`-by-`n` Hilbert matrix, whose element at `(i, j)` is
+    /// `1 / (i + j + 1)`.
+    ///
+    /// The Hilbert matrix is notoriously ill-conditioned, which makes it a
+    /// common test case for numerical algorithms. Over an exact type such as
+    /// `BigRational`, its elements are represented without rounding.
+    pub fn hilbert(n: usize) -> Self
+    where
+        T: Clone + One + Add<Output = T> + Div<Output = T>,
+    {
+        let mut denominators = Vec::with_capacity(2 * n);
+        let mut denominator = T::one();
+        for _ in 0..2 * n {
+            denominators.push(denominator.clone());
+            denominator = denominator + T::one();
+        }
+        from_fn(n, n, |i, j| T::one() / denominators[i + j].clone())
+    }
+
+    /// Creates the companion matrix of the polynomial whose coefficients,
+    /// from the highest degree to the lowest, are `coefficients`.
+    ///
+    /// For a polynomial of degree `n`, the companion matrix is `n`-by-`n`:
+    /// its first row holds `-coefficients[1..] / coefficients[0]`, its first
+    /// subdiagonal is filled with ones and all other elements are zero. Its
+    /// eigenvalues are the roots of the polynomial.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `coefficients` has fewer than two elements or if its first
+    /// element is zero.
+    #[track_caller]
+    pub fn companion(coefficients: &[T]) -> Self
+    where
+        T: Clone + Zero + One + Div<Output = T> + Neg<Output = T>,
+    {
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_len_failed(len: usize) -> ! {
+            panic!("`coefficients.len()` (is {len}) should be >= 2");
+        }
+
+        #[cold]
+        #[inline(never)]
+        #[track_caller]
+        fn assert_leading_failed() -> ! {
+            panic!("`coefficients[0]` should be nonzero");
+        }
+
+        if coefficients.len() < 2 {
+            assert_len_failed(coefficients.len());
+        }
+        if coefficients[0].is_zero() {
+            assert_leading_failed();
+        }
+
+        let leading = &coefficients[0];
+        from_fn(coefficients.len() - 1, coefficients.len() - 1, |i, j| {
+            if i == 0 {
+                -(coefficients[j + 1].clone() / leading.clone())
+            } else if i == j + 1 {
+                T::one()
+            } else {
+                T::zero()
+            }
+        })
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: Storage<T>,
+{
+    /// Returns an iterator over the elements of the main diagonal, that is,
+    /// the elements at `(i, i)` for `i` in `0..min(num_rows, num_columns)`.
+    pub fn diagonal(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        let len = self.num_rows.min(self.num_columns);
+        self.data
+            .as_slice()
+            .iter()
+            .step_by(self.leading_dimension + 1)
+            .take(len)
+    }
+
+    /// Returns the sum of the elements of the main diagonal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    #[track_caller]
+    pub fn trace(&self) -> T
+    where
+        T: Clone + Zero,
+    {
+        assert_square(self.num_rows, self.num_columns);
+        self.diagonal().fold(T::zero(), |sum, x| sum + x.clone())
+    }
+}
+
+impl<T, S> Matrix<T, S>
+where
+    S: StorageMut<T>,
+{
+    /// Returns an iterator that allows modifying each element of the main
+    /// diagonal.
+    pub fn diagonal_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + ExactSizeIterator {
+        let len = self.num_rows.min(self.num_columns);
+        let step = self.leading_dimension + 1;
+        self.data.as_mut_slice().iter_mut().step_by(step).take(len)
+    }
+}
+
+/// Creates a row-major `Matrix<T>` with shape `(num_rows, num_columns)`
+/// whose element at `(i, j)` is `f(i, j)`.
+fn from_fn<T, F>(num_rows: usize, num_columns: usize, mut f: F) -> Matrix<T>
+where
+    F: FnMut(usize, usize) -> T,
+{
+    let data = (0..num_rows)
+        .flat_map(|i| (0..num_columns).map(move |j| (i, j)))
+        .map(|(i, j)| f(i, j))
+        .collect();
+    Matrix::from_row_major(num_rows, num_columns, data)
+}
+
+#[cfg(test)]
+mod test_structured {
+    use crate::{Layout, Matrix};
+
+    #[test]
+    fn identity_and_eye() {
+        assert_eq!(Matrix::<i32>::identity(2), Matrix::from([[1, 0], [0, 1]]));
+        assert_eq!(Matrix::<i32>::identity(0).shape(), (0, 0));
+        assert_eq!(
+            Matrix::<i32>::eye(2, 3, 1),
+            Matrix::from([[0, 1, 0], [0, 0, 1]])
+        );
+        assert_eq!(
+            Matrix::<i32>::eye(3, 2, -1),
+            Matrix::from([[0, 0], [1, 0], [0, 1]])
+        );
+        assert_eq!(Matrix::<i32>::eye(2, 2, 5), Matrix::zeros(2, 2));
+    }
+
+    #[test]
+    fn diagonal() {
+        let mut a = Matrix::from_diagonal(&[1, 2, 3]);
+
+        assert_eq!(a, Matrix::from([[1, 0, 0], [0, 2, 0], [0, 0, 3]]));
+        assert_eq!(a.trace(), 6);
+
+        a.diagonal_mut().for_each(|x| *x *= 10);
+        assert_eq!(a.diagonal().copied().collect::<Vec<_>>(), [10, 20, 30]);
+
+        let b = Matrix::from_column_major(2, 3, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(b.diagonal().copied().collect::<Vec<_>>(), [0, 3]);
+        assert_eq!(b.to_layout(Layout::RowMajor).diagonal().len(), 2);
+
+        let c = Matrix::with_padding(2, 2, 4, 7);
+        assert_eq!(c.trace(), 14);
+    }
+
+    #[test]
+    #[should_panic(expected = "`num_rows` (is 2) should be equal to `num_columns` (is 3)")]
+    fn trace_of_non_square_matrix() {
+        Matrix::<i32>::zeros(2, 3).trace();
+    }
+
+    #[test]
+    fn toeplitz_hankel_and_circulant() {
+        assert_eq!(
+            Matrix::toeplitz(&[1, 2, 3], &[0, 4, 5, 6]),
+            Matrix::from([[1, 4, 5, 6], [2, 1, 4, 5], [3, 2, 1, 4]])
+        );
+        assert_eq!(
+            Matrix::hankel(&[1, 2, 3], &[0, 4, 5]),
+            Matrix::from([[1, 2, 3], [2, 3, 4], [3, 4, 5]])
+        );
+        assert_eq!(
+            Matrix::circulant(&[1, 2, 3]),
+            Matrix::from([[1, 3, 2], [2, 1, 3], [3, 2, 1]])
+        );
+        assert_eq!(Matrix::<i32>::circulant(&[]).shape(), (0, 0));
+    }
+
+    #[test]
+    fn vandermonde_and_hilbert() {
+        assert_eq!(
+            Matrix::vandermonde(&[1, 2, 3], 3),
+            Matrix::from([[1, 1, 1], [1, 2, 4], [1, 3, 9]])
+        );
+        assert_eq!(
+            Matrix::<f64>::hilbert(2),
+            Matrix::from([[1., 0.5], [0.5, 1. / 3.]])
+        );
+    }
+
+    #[test]
+    fn companion() {
+        // x^2 - 5x + 6 = (x - 2)(x - 3)
+        let a = Matrix::companion(&[2., -10., 12.]);
+
+        assert_eq!(a, Matrix::from([[5., -6.], [1., 0.]]));
+        assert_eq!(a.trace(), 5.);
+    }
+
+    #[test]
+    #[should_panic(expected = "`coefficients[0]` should be nonzero")]
+    fn companion_with_zero_leading_coefficient() {
+        Matrix::companion(&[0., 1., 2.]);
+    }
+}